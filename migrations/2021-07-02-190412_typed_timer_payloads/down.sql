@@ -0,0 +1,28 @@
+ALTER TABLE timers
+ADD COLUMN data TEXT;
+
+UPDATE timers SET data = CASE payload->>'type'
+	WHEN 'Reminder' THEN concat_ws('||', 'REMINDER',
+		payload->>'channel_id',
+		payload->>'user_id',
+		payload->>'duration',
+		payload->>'message')
+	WHEN 'Unmute' THEN concat_ws('||', 'UNMUTE',
+		payload->>'user_id',
+		payload->>'guild_id',
+		payload->>'role_id',
+		payload->>'channel_id',
+		payload->>'duration')
+	WHEN 'Cooldown' THEN concat_ws('||', 'COOLDOWN',
+		payload->>'user_id',
+		payload->>'guild_id',
+		payload->>'member_role',
+		payload->>'cooldown_role')
+	ELSE ''
+END;
+
+ALTER TABLE timers
+ALTER COLUMN data SET NOT NULL;
+
+ALTER TABLE timers
+DROP COLUMN payload;
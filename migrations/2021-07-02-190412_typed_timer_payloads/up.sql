@@ -0,0 +1,32 @@
+ALTER TABLE timers
+ADD COLUMN payload JSONB;
+
+UPDATE timers SET payload = CASE split_part(data, '||', 1)
+	WHEN 'REMINDER' THEN jsonb_build_object(
+		'type', 'Reminder',
+		'channel_id', split_part(data, '||', 2),
+		'user_id', split_part(data, '||', 3),
+		'duration', COALESCE(NULLIF(split_part(data, '||', 4), ''), '0')::BIGINT,
+		'message', array_to_string((string_to_array(data, '||'))[5:], '||'))
+	WHEN 'UNMUTE' THEN jsonb_build_object(
+		'type', 'Unmute',
+		'user_id', split_part(data, '||', 2),
+		'guild_id', split_part(data, '||', 3),
+		'role_id', split_part(data, '||', 4),
+		'channel_id', split_part(data, '||', 5),
+		'duration', COALESCE(NULLIF(split_part(data, '||', 6), ''), '0')::BIGINT)
+	WHEN 'COOLDOWN' THEN jsonb_build_object(
+		'type', 'Cooldown',
+		'user_id', split_part(data, '||', 2),
+		'guild_id', split_part(data, '||', 3),
+		'member_role', split_part(data, '||', 4),
+		'cooldown_role', split_part(data, '||', 5))
+END;
+
+DELETE FROM timers WHERE payload IS NULL;
+
+ALTER TABLE timers
+ALTER COLUMN payload SET NOT NULL;
+
+ALTER TABLE timers
+DROP COLUMN data;
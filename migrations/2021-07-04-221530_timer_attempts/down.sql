@@ -0,0 +1,8 @@
+ALTER TABLE timers
+DROP COLUMN attempts;
+
+ALTER TABLE timers
+DROP COLUMN last_error;
+
+ALTER TABLE timers
+DROP COLUMN failed;
@@ -0,0 +1,8 @@
+ALTER TABLE timers
+ADD COLUMN attempts INT NOT NULL DEFAULT 0;
+
+ALTER TABLE timers
+ADD COLUMN last_error TEXT;
+
+ALTER TABLE timers
+ADD COLUMN failed BOOL NOT NULL DEFAULT 'f';
@@ -0,0 +1,7 @@
+DROP INDEX timers_owner_id_idx;
+
+ALTER TABLE timers
+DROP COLUMN owner_id;
+
+ALTER TABLE timers
+DROP COLUMN guild_id;
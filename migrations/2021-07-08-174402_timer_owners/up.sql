@@ -0,0 +1,11 @@
+ALTER TABLE timers
+ADD COLUMN owner_id BIGINT;
+
+ALTER TABLE timers
+ADD COLUMN guild_id BIGINT;
+
+UPDATE timers SET
+	owner_id = (payload->>'user_id')::BIGINT,
+	guild_id = (payload->>'guild_id')::BIGINT;
+
+CREATE INDEX timers_owner_id_idx ON timers (owner_id);
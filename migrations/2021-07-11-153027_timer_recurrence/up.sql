@@ -0,0 +1,2 @@
+ALTER TABLE timers
+ADD COLUMN recurrence TEXT;
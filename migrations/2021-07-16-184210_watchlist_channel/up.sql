@@ -0,0 +1,2 @@
+ALTER TABLE guilds
+ADD COLUMN watchlist_channel BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+DROP TABLE case_counters;
+
+ALTER TABLE cases
+DROP COLUMN modlog_message;
+
+ALTER TABLE cases
+DROP COLUMN modlog_channel;
+
+DROP INDEX cases_guild_case_number_idx;
+
+ALTER TABLE cases
+DROP COLUMN case_number;
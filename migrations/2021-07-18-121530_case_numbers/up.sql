@@ -0,0 +1,28 @@
+ALTER TABLE cases
+ADD COLUMN case_number INT;
+
+UPDATE cases SET case_number = numbered.case_number
+FROM (
+	SELECT id, row_number() OVER (PARTITION BY guild_id ORDER BY id) AS case_number
+	FROM cases
+) AS numbered
+WHERE cases.id = numbered.id;
+
+ALTER TABLE cases
+ALTER COLUMN case_number SET NOT NULL;
+
+CREATE UNIQUE INDEX cases_guild_case_number_idx ON cases (guild_id, case_number);
+
+ALTER TABLE cases
+ADD COLUMN modlog_channel BIGINT;
+
+ALTER TABLE cases
+ADD COLUMN modlog_message BIGINT;
+
+CREATE TABLE case_counters (
+	guild_id BIGINT PRIMARY KEY,
+	last_case INT NOT NULL
+);
+
+INSERT INTO case_counters (guild_id, last_case)
+SELECT guild_id, MAX(case_number) FROM cases GROUP BY guild_id;
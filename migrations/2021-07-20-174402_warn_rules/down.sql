@@ -0,0 +1,4 @@
+ALTER TABLE cases
+DROP COLUMN pardoned;
+
+DROP TABLE warn_rules;
@@ -0,0 +1,12 @@
+CREATE TABLE warn_rules (
+	id SERIAL,
+	guild_id BIGINT NOT NULL,
+	warns INT NOT NULL,
+	period BIGINT NOT NULL DEFAULT 0,
+	action TEXT NOT NULL,
+	duration BIGINT,
+	PRIMARY KEY(id, guild_id)
+);
+
+ALTER TABLE cases
+ADD COLUMN pardoned BOOLEAN NOT NULL DEFAULT false;
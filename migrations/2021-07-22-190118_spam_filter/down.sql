@@ -0,0 +1,26 @@
+ALTER TABLE guilds
+DROP COLUMN spam_mute_duration;
+
+ALTER TABLE guilds
+DROP COLUMN spam_mention_action;
+
+ALTER TABLE guilds
+DROP COLUMN spam_mention_count;
+
+ALTER TABLE guilds
+DROP COLUMN spam_duplicate_action;
+
+ALTER TABLE guilds
+DROP COLUMN spam_duplicate_seconds;
+
+ALTER TABLE guilds
+DROP COLUMN spam_duplicate_count;
+
+ALTER TABLE guilds
+DROP COLUMN spam_flood_action;
+
+ALTER TABLE guilds
+DROP COLUMN spam_flood_seconds;
+
+ALTER TABLE guilds
+DROP COLUMN spam_flood_count;
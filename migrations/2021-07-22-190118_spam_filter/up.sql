@@ -0,0 +1,26 @@
+ALTER TABLE guilds
+ADD COLUMN spam_flood_count INT NOT NULL DEFAULT 0;
+
+ALTER TABLE guilds
+ADD COLUMN spam_flood_seconds INT NOT NULL DEFAULT 5;
+
+ALTER TABLE guilds
+ADD COLUMN spam_flood_action TEXT NOT NULL DEFAULT 'delete';
+
+ALTER TABLE guilds
+ADD COLUMN spam_duplicate_count INT NOT NULL DEFAULT 0;
+
+ALTER TABLE guilds
+ADD COLUMN spam_duplicate_seconds INT NOT NULL DEFAULT 60;
+
+ALTER TABLE guilds
+ADD COLUMN spam_duplicate_action TEXT NOT NULL DEFAULT 'delete';
+
+ALTER TABLE guilds
+ADD COLUMN spam_mention_count INT NOT NULL DEFAULT 0;
+
+ALTER TABLE guilds
+ADD COLUMN spam_mention_action TEXT NOT NULL DEFAULT 'delete';
+
+ALTER TABLE guilds
+ADD COLUMN spam_mute_duration BIGINT NOT NULL DEFAULT 600;
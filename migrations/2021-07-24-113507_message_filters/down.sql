@@ -0,0 +1,4 @@
+ALTER TABLE guilds
+DROP COLUMN filter_warn;
+
+DROP TABLE filters;
@@ -0,0 +1,10 @@
+CREATE TABLE filters (
+	id SERIAL,
+	guild_id BIGINT NOT NULL,
+	pattern TEXT NOT NULL,
+	regex BOOLEAN NOT NULL DEFAULT false,
+	PRIMARY KEY(id, guild_id)
+);
+
+ALTER TABLE guilds
+ADD COLUMN filter_warn BOOLEAN NOT NULL DEFAULT false;
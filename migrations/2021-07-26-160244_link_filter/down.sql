@@ -0,0 +1,14 @@
+ALTER TABLE guilds
+DROP COLUMN link_filter_action;
+
+ALTER TABLE guilds
+DROP COLUMN allowed_domains;
+
+ALTER TABLE guilds
+DROP COLUMN blocked_domains;
+
+ALTER TABLE guilds
+DROP COLUMN invite_allow_own;
+
+ALTER TABLE guilds
+DROP COLUMN invite_filter;
@@ -0,0 +1,14 @@
+ALTER TABLE guilds
+ADD COLUMN invite_filter BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE guilds
+ADD COLUMN invite_allow_own BOOLEAN NOT NULL DEFAULT true;
+
+ALTER TABLE guilds
+ADD COLUMN blocked_domains TEXT [] NOT NULL DEFAULT array[]::text[];
+
+ALTER TABLE guilds
+ADD COLUMN allowed_domains TEXT [] NOT NULL DEFAULT array[]::text[];
+
+ALTER TABLE guilds
+ADD COLUMN link_filter_action TEXT NOT NULL DEFAULT 'delete';
@@ -0,0 +1,25 @@
+ALTER TABLE guilds
+DROP COLUMN raid_verification_level;
+
+ALTER TABLE guilds
+DROP COLUMN raid_active;
+
+ALTER TABLE guilds
+DROP COLUMN raid_punish;
+
+ALTER TABLE guilds
+DROP COLUMN raid_channels;
+
+ALTER TABLE guilds
+DROP COLUMN raid_action;
+
+ALTER TABLE guilds
+DROP COLUMN raid_account_age;
+
+ALTER TABLE guilds
+DROP COLUMN raid_seconds;
+
+ALTER TABLE guilds
+DROP COLUMN raid_joins;
+
+DROP TABLE channel_locks;
@@ -0,0 +1,31 @@
+CREATE TABLE channel_locks (
+	channel_id BIGINT PRIMARY KEY,
+	guild_id BIGINT NOT NULL,
+	overwrites JSONB NOT NULL,
+	raid BOOLEAN NOT NULL DEFAULT false,
+	timestamp TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT now()
+);
+
+ALTER TABLE guilds
+ADD COLUMN raid_joins INT NOT NULL DEFAULT 0;
+
+ALTER TABLE guilds
+ADD COLUMN raid_seconds INT NOT NULL DEFAULT 10;
+
+ALTER TABLE guilds
+ADD COLUMN raid_account_age BIGINT NOT NULL DEFAULT 0;
+
+ALTER TABLE guilds
+ADD COLUMN raid_action TEXT NOT NULL DEFAULT 'verification';
+
+ALTER TABLE guilds
+ADD COLUMN raid_channels BIGINT [] NOT NULL DEFAULT array[]::bigint[];
+
+ALTER TABLE guilds
+ADD COLUMN raid_punish TEXT NOT NULL DEFAULT 'none';
+
+ALTER TABLE guilds
+ADD COLUMN raid_active BOOLEAN NOT NULL DEFAULT false;
+
+ALTER TABLE guilds
+ADD COLUMN raid_verification_level SMALLINT;
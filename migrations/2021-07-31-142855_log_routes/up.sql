@@ -0,0 +1,2 @@
+ALTER TABLE guilds
+ADD COLUMN log_routes JSONB NOT NULL DEFAULT '{}'::jsonb;
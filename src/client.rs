@@ -2,6 +2,7 @@ use crate::standard_framework::StandardFramework;
 use rand::Rng;
 use tracing::{event, Level};
 use momiji::Context;
+use momiji::core::automod::AutomodState;
 use momiji::core::timers::TimerClient;
 use momiji::db::DatabaseConnection;
 use momiji::{core::handler::EventHandler};
@@ -49,6 +50,7 @@ impl Client {
         let tc = TimerClient::new(http.clone(), cache.clone(), db.clone());
 
         let ctx = Context {
+            automod: AutomodState::new(),
             cache,
             cluster,
             db,
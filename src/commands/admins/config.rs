@@ -1,7 +1,11 @@
 use momiji::Context;
+use momiji::core::antiraid::{RAID_ACTIONS, RAID_PUNISHMENTS};
+use momiji::core::automod::{self, AUTOMOD_ACTIONS};
 use momiji::core::consts::*;
+use momiji::core::moderation::ESCALATION_ACTIONS;
 use momiji::core::utils::*;
 use momiji::framework::args::Args;
+use momiji::db::models::Filter;
 use momiji::framework::command::{Command, Options};
 use tracing::debug;
 use twilight_embed_builder::EmbedBuilder;
@@ -630,14 +634,520 @@ impl Command for ConfigCommands {
     }
 }
 
+pub struct ConfigWatchlist;
+#[async_trait]
+impl Command for ConfigWatchlist {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the channel activity of watchlisted users is mirrored to. A channel must be provided for channel.".to_string()),
+            usage: Some("<channel|disable> <channel_resolvable>".to_string()),
+            examples: vec!["channel #watchlist".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let op = args.single::<String>().unwrap_or(String::new());
+            let mut val = args.rest().to_string();
+            match op.to_lowercase().as_str() {
+                "disable" => {
+                    guild_data.watchlist_channel = 0;
+                },
+                "channel" => {
+                    match parse_channel(val.to_string(), guild_id, ctx.clone()) {
+                        Some((channel_id, channel)) => {
+                            guild_data.watchlist_channel = channel_id.0 as i64;
+                            val = format!("{} ({})", channel.name(), channel_id.0);
+                        },
+                        None => {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that channel.")?.await?;
+                            return Ok(())
+                        },
+                    }
+                },
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `channel`, `disable`. For more information see `help config watchlist`")?.await?;
+                    return Ok(())
+                },
+            }
+            ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+
+            let embed = EmbedBuilder::new()
+                .title("Config Watchlist Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}",
+                    op,
+                    val,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConfigFilter;
+#[async_trait]
+impl Command for ConfigFilter {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the word filter. Messages containing a filtered word or matching a filtered pattern are deleted and recorded in the audit channel. Words match whole words, add `/regex` to add a regular expression instead; escape any `/` in it as `\\/`. `warn` toggles warning the author.".to_string()),
+            usage: Some("<add|remove|list|warn> [word|pattern|id|on|off] [/regex]".to_string()),
+            examples: vec!["add badword".to_string(), "add fr[e3]{2} nitro /regex".to_string(), "remove 3".to_string(), "warn on".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let op = args.single::<String>().unwrap_or(String::new());
+            let val = match op.to_lowercase().as_str() {
+                "add" => {
+                    let switches = get_switches(args.rest().to_string());
+                    let pattern = switches.get("rest").cloned().unwrap_or(String::new());
+                    let regex = switches.contains_key("regex");
+                    if pattern.is_empty() {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a word or pattern.")?.await?;
+                        return Ok(())
+                    }
+                    let filter = Filter { id: 0, guild_id: guild_id.0 as i64, pattern: pattern.clone(), regex };
+                    if let Err(why) = automod::filter_regex(&filter) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content(format!("That isn't a valid pattern: {}", why))?.await?;
+                        return Ok(())
+                    }
+                    let filter = ctx.db.new_filter(guild_id.0 as i64, pattern, regex)?;
+                    ctx.automod.forget_filters(guild_id);
+                    filter.to_string()
+                },
+                "remove" => {
+                    match ctx.db.del_filter(args.single::<i32>().unwrap_or(0), guild_id.0 as i64) {
+                        Ok(filter) => {
+                            ctx.automod.forget_filters(guild_id);
+                            filter.to_string()
+                        },
+                        Err(_) => {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a filter with that ID. See `config filter list`.")?.await?;
+                            return Ok(())
+                        },
+                    }
+                },
+                "warn" => {
+                    let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    guild_data.filter_warn = match args.single::<String>().unwrap_or(String::new()).to_lowercase().as_str() {
+                        "on" | "true" | "enable" => true,
+                        "off" | "false" | "disable" => false,
+                        _ => !guild_data.filter_warn,
+                    };
+                    ctx.db.update_guild(guild_id.0 as i64, guild_data)?.filter_warn.to_string()
+                },
+                "list" => {
+                    let filters = ctx.db.get_filters(guild_id.0 as i64)?;
+                    let embed = EmbedBuilder::new()
+                        .title("Filters")
+                        .color(colors::MAIN)
+                        .description(if filters.is_empty() {
+                            "No filters".to_string()
+                        } else {
+                            filters.iter().map(|f| f.to_string()).collect::<Vec<String>>().join("\n")
+                        })
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+                    return Ok(())
+                },
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `add`, `remove`, `list`, `warn`. For more information see `help config filter`")?.await?;
+                    return Ok(())
+                },
+            };
+
+            let embed = EmbedBuilder::new()
+                .title("Config Filter Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}",
+                    op,
+                    val,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConfigLinks;
+#[async_trait]
+impl Command for ConfigLinks {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the link filter. `invites` toggles removing Discord invites and `own` toggles allowing invites to this server. `block` and `unblock` manage the domain blocklist, which includes subdomains; `allow` and `disallow` manage domains that are never blocked. `action` is one of delete, warn, mute or kick.".to_string()),
+            usage: Some("<invites|own|block|unblock|allow|disallow|action> <value>".to_string()),
+            examples: vec!["invites on".to_string(), "own off".to_string(), "block discord-nitro.gift".to_string(), "allow discord.gift".to_string(), "action warn".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let op = args.single::<String>().unwrap_or(String::new());
+            let val = args.rest().trim().to_lowercase();
+            let toggle = |current: bool| match val.as_str() {
+                "on" | "true" | "enable" => true,
+                "off" | "false" | "disable" => false,
+                _ => !current,
+            };
+            let domain = val.trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .trim_end_matches('/')
+                .to_string();
+            match op.to_lowercase().as_str() {
+                "invites" => { guild_data.invite_filter = toggle(guild_data.invite_filter); },
+                "own" => { guild_data.invite_allow_own = toggle(guild_data.invite_allow_own); },
+                "block" if !domain.is_empty() => {
+                    if !guild_data.blocked_domains.contains(&domain) { guild_data.blocked_domains.push(domain); }
+                },
+                "unblock" => { guild_data.blocked_domains.retain(|d| *d != domain); },
+                "allow" if !domain.is_empty() => {
+                    if !guild_data.allowed_domains.contains(&domain) { guild_data.allowed_domains.push(domain); }
+                },
+                "disallow" => { guild_data.allowed_domains.retain(|d| *d != domain); },
+                "action" if AUTOMOD_ACTIONS.contains(&val.as_str()) => { guild_data.link_filter_action = val.clone(); },
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `invites`, `own`, `block`, `unblock`, `allow`, `disallow`, `action`. For more information see `help config links`")?.await?;
+                    return Ok(())
+                },
+            }
+            let guild_data = ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+
+            let embed = EmbedBuilder::new()
+                .title("Config Links Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}\n**Invite Filter:** {}\n**Own Invites Allowed:** {}\n**Blocked Domains:** {}\n**Allowed Domains:** {}\n**Action:** {}",
+                    op,
+                    val,
+                    guild_data.invite_filter,
+                    guild_data.invite_allow_own,
+                    guild_data.blocked_domains.join(", "),
+                    guild_data.allowed_domains.join(", "),
+                    guild_data.link_filter_action,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConfigSpam;
+#[async_trait]
+impl Command for ConfigSpam {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the spam filters. `flood` limits how many messages a member can send within a number of seconds, `duplicates` limits identical messages and `mentions` limits mentions in one message. Actions are delete, warn, mute or kick. `mute_time` sets how long automod mutes last. Members with a mod or admin role and ignored channels are exempt.".to_string()),
+            usage: Some("<flood|duplicates|mentions|mute_time> <count|off|time> [/s seconds] [/a action]".to_string()),
+            examples: vec!["flood 6 /s 5 /a mute".to_string(), "duplicates 3 /s 60 /a warn".to_string(), "mentions 8 /a kick".to_string(), "mute_time 30m".to_string(), "flood off".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let op = args.single::<String>().unwrap_or(String::new()).to_lowercase();
+            let switches = get_switches(args.rest().to_string());
+            let rest = switches.get("rest").cloned().unwrap_or(String::new()).trim().to_lowercase();
+            if op == "mute_time" {
+                let time = hrtime_to_seconds(rest.clone());
+                if time <= 0 {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a time, such as `30m`.")?.await?;
+                    return Ok(())
+                }
+                guild_data.spam_mute_duration = time;
+            } else {
+                let count = if rest == "off" { 0 } else {
+                    match rest.parse::<i32>() {
+                        Ok(count) if count > 0 => count,
+                        _ => {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a count, or `off`.")?.await?;
+                            return Ok(())
+                        },
+                    }
+                };
+                let seconds = switches.get("s").and_then(|s| s.trim().parse::<i32>().ok()).filter(|s| *s > 0);
+                let action = switches.get("a").map(|a| a.trim().to_lowercase());
+                if let Some(action) = &action {
+                    if !AUTOMOD_ACTIONS.contains(&action.as_str()) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Invalid action. Valid actions are: `delete`, `warn`, `mute`, `kick`.")?.await?;
+                        return Ok(())
+                    }
+                }
+                match op.as_str() {
+                    "flood" => {
+                        guild_data.spam_flood_count = count;
+                        if let Some(seconds) = seconds { guild_data.spam_flood_seconds = seconds; }
+                        if let Some(action) = action { guild_data.spam_flood_action = action; }
+                    },
+                    "duplicates" | "duplicate" => {
+                        guild_data.spam_duplicate_count = count;
+                        if let Some(seconds) = seconds { guild_data.spam_duplicate_seconds = seconds; }
+                        if let Some(action) = action { guild_data.spam_duplicate_action = action; }
+                    },
+                    "mentions" | "mention" => {
+                        guild_data.spam_mention_count = count;
+                        if let Some(action) = action { guild_data.spam_mention_action = action; }
+                    },
+                    _ => {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `flood`, `duplicates`, `mentions`, `mute_time`. For more information see `help config spam`")?.await?;
+                        return Ok(())
+                    },
+                }
+            }
+            let guild_data = ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+            let val = match op.as_str() {
+                "flood" if guild_data.spam_flood_count > 0 => format!("{} messages in {}s, {}", guild_data.spam_flood_count, guild_data.spam_flood_seconds, guild_data.spam_flood_action),
+                "duplicates" | "duplicate" if guild_data.spam_duplicate_count > 0 => format!("{} messages in {}s, {}", guild_data.spam_duplicate_count, guild_data.spam_duplicate_seconds, guild_data.spam_duplicate_action),
+                "mentions" | "mention" if guild_data.spam_mention_count > 0 => format!("{} mentions, {}", guild_data.spam_mention_count, guild_data.spam_mention_action),
+                "mute_time" => seconds_to_hrtime(guild_data.spam_mute_duration as usize),
+                _ => String::from("Off"),
+            };
+
+            let embed = EmbedBuilder::new()
+                .title("Config Spam Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}",
+                    op,
+                    val,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConfigRaid;
+#[async_trait]
+impl Command for ConfigRaid {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change raid protection. `joins` sets how many members may join within a number of seconds before a raid is declared, and `age` makes accounts younger than the given time count as two joins. When a raid is detected the `action` is taken: `verification` raises the verification level, `lock` locks the configured `channels` (or every text channel if none are set). `punish` kicks or bans the members who joined in the raid. Use `antiraid off` to restore the server afterwards.".to_string()),
+            usage: Some("<joins|age|action|channels|punish> <value> [/s seconds]".to_string()),
+            examples: vec!["joins 10 /s 15".to_string(), "age 3d".to_string(), "action lock".to_string(), "channels #general #memes".to_string(), "punish kick".to_string(), "joins off".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let op = args.single::<String>().unwrap_or(String::new()).to_lowercase();
+            let switches = get_switches(args.rest().to_string());
+            let rest = switches.get("rest").cloned().unwrap_or(String::new()).trim().to_lowercase();
+            match op.as_str() {
+                "joins" => {
+                    guild_data.raid_joins = if rest == "off" { 0 } else {
+                        match rest.parse::<i32>() {
+                            Ok(count) if count > 1 => count,
+                            _ => {
+                                ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a count of at least 2, or `off`.")?.await?;
+                                return Ok(())
+                            },
+                        }
+                    };
+                    if let Some(seconds) = switches.get("s").and_then(|s| s.trim().parse::<i32>().ok()).filter(|s| *s > 0) {
+                        guild_data.raid_seconds = seconds;
+                    }
+                },
+                "age" => {
+                    guild_data.raid_account_age = if rest == "off" { 0 } else {
+                        let time = hrtime_to_seconds(rest.clone());
+                        if time <= 0 {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a time, such as `3d`, or `off`.")?.await?;
+                            return Ok(())
+                        }
+                        time
+                    };
+                },
+                "action" => {
+                    if !RAID_ACTIONS.contains(&rest.as_str()) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Invalid action. Valid actions are: `verification`, `lock`.")?.await?;
+                        return Ok(())
+                    }
+                    guild_data.raid_action = rest.clone();
+                },
+                "channels" | "channel" => {
+                    if rest == "clear" {
+                        guild_data.raid_channels.clear();
+                    } else {
+                        let mut channels = Vec::new();
+                        for input in rest.split_whitespace() {
+                            match parse_channel(input.to_string(), guild_id, ctx.clone()) {
+                                Some((channel_id, _)) => { channels.push(channel_id.0 as i64); },
+                                None => {
+                                    ctx.http.create_message(message.channel_id).reply(message.id).content(format!("I couldn't find the channel `{}`.", input))?.await?;
+                                    return Ok(())
+                                },
+                            }
+                        }
+                        if channels.is_empty() {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide one or more channels, or `clear`.")?.await?;
+                            return Ok(())
+                        }
+                        guild_data.raid_channels = channels;
+                    }
+                },
+                "punish" | "punishment" => {
+                    if !RAID_PUNISHMENTS.contains(&rest.as_str()) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Invalid punishment. Valid punishments are: `none`, `kick`, `ban`.")?.await?;
+                        return Ok(())
+                    }
+                    guild_data.raid_punish = rest.clone();
+                },
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `joins`, `age`, `action`, `channels`, `punish`. For more information see `help config raid`")?.await?;
+                    return Ok(())
+                },
+            }
+            let guild_data = ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+            let val = match op.as_str() {
+                "joins" if guild_data.raid_joins > 0 => format!("{} joins in {}s", guild_data.raid_joins, guild_data.raid_seconds),
+                "age" if guild_data.raid_account_age > 0 => seconds_to_hrtime(guild_data.raid_account_age as usize),
+                "action" => guild_data.raid_action.clone(),
+                "channels" | "channel" if !guild_data.raid_channels.is_empty() => guild_data.raid_channels.iter().map(|id| format!("<#{}>", id)).collect::<Vec<String>>().join(", "),
+                "channels" | "channel" => String::from("All text channels"),
+                "punish" | "punishment" => guild_data.raid_punish.clone(),
+                _ => String::from("Off"),
+            };
+
+            let embed = EmbedBuilder::new()
+                .title("Config Raid Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}",
+                    op,
+                    val,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ConfigWarns;
+#[async_trait]
+impl Command for ConfigWarns {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change what happens when a user collects warnings. A rule fires when a user reaches its number of warnings, counting only warnings within the period if one is given. Actions are mute, kick or ban; a time makes the mute or ban temporary.".to_string()),
+            usage: Some("<add|remove|list> [count action|id] [/p period] [/t time]".to_string()),
+            examples: vec!["add 3 mute /p 30d /t 1h".to_string(), "add 5 kick".to_string(), "remove 2".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let op = args.single::<String>().unwrap_or(String::new());
+            let val = match op.to_lowercase().as_str() {
+                "add" => {
+                    let warns = args.single::<i32>().unwrap_or(0);
+                    let action = args.single::<String>().unwrap_or(String::new()).to_lowercase();
+                    if warns < 1 || !ESCALATION_ACTIONS.contains(&action.as_str()) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a number of warnings followed by `mute`, `kick` or `ban`.")?.await?;
+                        return Ok(())
+                    }
+                    let switches = get_switches(args.rest().to_string());
+                    let period = switches.get("p")
+                        .map(|s| hrtime_to_seconds(s.clone()))
+                        .unwrap_or(0);
+                    let duration = switches.get("t")
+                        .map(|s| hrtime_to_seconds(s.clone()))
+                        .filter(|t| *t > 0 && action != "kick");
+                    ctx.db.new_warn_rule(guild_id.0 as i64, warns, period, action, duration)?.to_string()
+                },
+                "remove" => {
+                    match ctx.db.del_warn_rule(args.single::<i32>().unwrap_or(0), guild_id.0 as i64) {
+                        Ok(rule) => rule.to_string(),
+                        Err(_) => {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a rule with that ID. See `config warns list`.")?.await?;
+                            return Ok(())
+                        },
+                    }
+                },
+                "list" => {
+                    let rules = ctx.db.get_warn_rules(guild_id.0 as i64)?;
+                    let embed = EmbedBuilder::new()
+                        .title("Warning Escalations")
+                        .color(colors::MAIN)
+                        .description(if rules.is_empty() {
+                            "No rules".to_string()
+                        } else {
+                            rules.iter().map(|r| r.to_string()).collect::<Vec<String>>().join("\n")
+                        })
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+                    return Ok(())
+                },
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `add`, `remove`, `list`. For more information see `help config warns`")?.await?;
+                    return Ok(())
+                },
+            };
+
+            let embed = EmbedBuilder::new()
+                .title("Config Warns Summary")
+                .color(colors::MAIN)
+                .description(format!("**Operation:** {}\n**Value:** {}",
+                    op,
+                    val,
+                ))
+                .build()?;
+
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
 pub struct ConfigLogs;
 #[async_trait]
 impl Command for ConfigLogs {
     fn options(&self) -> Arc<Options> {
         let options = Options {
-            description: Some("Change which log messages are disabled. A log type must be provided.".to_string()),
-            usage: Some("<enable|disable|types> [type]".to_string()),
-            examples: vec!["disable message_edit".to_string()],
+            description: Some("Change which log messages are disabled, or route a log type to its own channel. A log type must be provided. Routed log types are posted to their channel instead of the audit or modlog channel; use `off` to remove a route.".to_string()),
+            usage: Some("<enable|disable|route|types> [type] [channel_resolvable|off]".to_string()),
+            examples: vec!["disable message_edit".to_string(), "route member_join #joins".to_string(), "route member_join off".to_string()],
             required_permissions: Permissions::MANAGE_GUILD,
             guild_only: true,
             ..Options::default()
@@ -649,11 +1159,36 @@ impl Command for ConfigLogs {
         if let Some(guild_id) = message.guild_id {
             let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
             let op = args.single::<String>().unwrap_or(String::new());
-            let val = args.rest().to_string();
+            let mut val = args.rest().to_string();
             match op.to_lowercase().as_str() {
                 "enable" => {
                     guild_data.logging.retain(|e| *e != val);
                 },
+                "route" => {
+                    let log_type = args.single::<String>().unwrap_or(String::new()).to_lowercase();
+                    let channel = args.rest().trim().to_string();
+                    if !LOG_TYPES.contains(&log_type.as_str()) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Invalid log type. See `config log types` for valid types.")?.await?;
+                        return Ok(());
+                    }
+                    let mut routes = guild_data.log_routes.as_object().cloned().unwrap_or_default();
+                    if channel.to_lowercase() == "off" {
+                        routes.remove(&log_type);
+                        val = format!("{} → default channel", log_type);
+                    } else {
+                        match parse_channel(channel, guild_id, ctx.clone()) {
+                            Some((channel_id, channel)) => {
+                                routes.insert(log_type.clone(), (channel_id.0 as i64).into());
+                                val = format!("{} → {} ({})", log_type, channel.name(), channel_id.0);
+                            },
+                            None => {
+                                ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that channel.")?.await?;
+                                return Ok(())
+                            },
+                        }
+                    }
+                    guild_data.log_routes = routes.into();
+                },
                 "disable" => {
                     if LOG_TYPES.contains(&val.as_str()) {
                         guild_data.logging.push(val.clone());
@@ -671,7 +1206,7 @@ impl Command for ConfigLogs {
                     return Ok(());
                 },
                 _ => {
-                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `enable`, `disable`. For more information see `help config log`")?.await?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I didn't understand that option. Valid options are: `enable`, `disable`, `route`, `types`. For more information see `help config log`")?.await?;
                     return Ok(())
                 },
             }
@@ -1,6 +1,7 @@
 use chrono::Utc;
 use momiji::Context;
 use momiji::core::consts::*;
+use momiji::core::moderation;
 use momiji::core::utils::*;
 use momiji::framework::args::Args;
 use momiji::framework::command::{Command, Options};
@@ -196,56 +197,62 @@ impl Command for Prune {
 //     }
 // }
 
-// pub struct SetupMute;
-// #[async_trait]
-// impl Command for SetupMute {
-//     fn options(&self) -> Arc<Options> {
-//         let options = Options {
-//             description: Some("Sets up mute for the server. This command requires the Manage Channels and Manage Roles permissions. It creates the Muted role if it doesn't exist, then iterates through every channel and category to disable Send Messages, Speak, and Add Reactions. Add `bypass` as an arg to skip permission setting.".to_string()),
-//             required_permissions: Permissions::MANAGE_GUILD,
-//             ..Options::default()
-//         };
-//         Arc::new(options)
-//     }
+pub struct SetupMute;
+#[async_trait]
+impl Command for SetupMute {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Sets up mute for the server. This command requires the Manage Channels and Manage Roles permissions. It creates the Muted role if it doesn't exist, then iterates through every channel and category to disable Send Messages, Speak, and Add Reactions. Add `bypass` as an arg to skip permission setting.".to_string()),
+            usage: Some("[bypass]".to_string()),
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
 
-//     async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
-//         if let Some(guild_id) = message.guild_id {
-//             let guild = {
-//                 let ctx.cache = ctx.cache.read();
-//                 ctx.cache.guild(guild_id)
-//             };
-//             if let Some(guild_lock) = guild {
-//                 let guild = guild_lock.read().clone();
-//                 let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
-//                 let bypass = args.single::<String>().unwrap_or("".to_string());
-//                 let mute_role = match guild.roles.values().find(|e| e.name.to_lowercase() == "muted") {
-//                     Some(role) => role.clone(),
-//                     None => {
-//                         message.channel_id.say("Role `Muted` created")?;
-//                         guild.create_role(|r| r.name("Muted"))?
-//                     },
-//                 };
-//                 if bypass != "bypass" {
-//                     let allow = Permissions::empty();
-//                     let deny = Permissions::SEND_MESSAGES | Permissions::ADD_REACTIONS | Permissions::SPEAK;
-//                     let overwrite = PermissionOverwrite {
-//                         allow,
-//                         deny,
-//                         kind: PermissionOverwriteType::Role(mute_role.id),
-//                     };
-//                     for channel in guild.channels.values() {
-//                         let mut channel = channel.read();
-//                         channel.create_permission(&overwrite)?;
-//                     }
-//                 }
-//                 guild_data.mute_setup = true;
-//                 ctx.db.update_guild(guild.id.0 as i64, guild_data)?;
-//                 message.channel_id.say(format!("Setup permissions for {} channels.", guild.channels.len()))?;
-//             }
-//         } else { failed!(GUILDID_FAIL); }
-//         Ok(())
-//     }
-// }
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let bypass = args.single::<String>().unwrap_or(String::new());
+            let mute_role = match moderation::find_mute_role(guild_id, &ctx) {
+                Some(role) => role.id,
+                None => {
+                    let role = ctx.http.create_role(guild_id)
+                        .name("Muted")
+                        .await?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Role `Muted` created")?.await?;
+                    role.id
+                },
+            };
+            let mut count = 0;
+            let mut failed = Vec::new();
+            if bypass != "bypass" {
+                let allow = Permissions::empty();
+                let deny = Permissions::SEND_MESSAGES | Permissions::ADD_REACTIONS | Permissions::SPEAK;
+                let channels = ctx.cache.guild_channels(guild_id).unwrap_or_default();
+                for channel_id in channels {
+                    match ctx.http.update_channel_permission(channel_id, allow, deny).role(mute_role).await {
+                        Ok(_) => { count += 1; },
+                        Err(why) => {
+                            event!(Level::DEBUG, "Failed to set mute overwrite on {}: {}", channel_id, why);
+                            failed.push(channel_id.mention().to_string());
+                        },
+                    }
+                }
+            }
+            guild_data.mute_setup = true;
+            ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+            let mut response = format!("Setup permissions for {} channels.", count);
+            if !failed.is_empty() {
+                response.push_str(format!(" I couldn't update {}.", failed.join(", ")).as_str());
+            }
+            ctx.http.create_message(message.channel_id).reply(message.id).content(response)?.await?;
+        }
+
+        Ok(())
+    }
+}
 
 fn is_deletable(message: &Message) -> bool {
     let now = Utc::now().timestamp();
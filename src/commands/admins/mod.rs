@@ -3,6 +3,7 @@ pub mod config;
 pub mod management;
 pub mod register_control;
 pub mod roles;
+pub mod schedule;
 // pub mod tests;
 
 use self::config::*;
@@ -10,6 +11,7 @@ use self::config::*;
 use self::management::*;
 use self::register_control::*;
 use self::roles::*;
+use self::schedule::*;
 // use self::tests::*;
 use momiji::framework::command::{CommandOrAlias::*, ModuleBuilder};
 use std::sync::Arc;
@@ -33,6 +35,12 @@ pub fn init_config(module: ModuleBuilder) -> ModuleBuilder {
         .add_command("intro", Alias("introduction".to_string()))
         .add_command("cmd", Command(Arc::new(ConfigCommands)))
         .add_command("log", Command(Arc::new(ConfigLogs)))
+        .add_command("watchlist", Command(Arc::new(ConfigWatchlist)))
+        .add_command("warns", Command(Arc::new(ConfigWarns)))
+        .add_command("spam", Command(Arc::new(ConfigSpam)))
+        .add_command("filter", Command(Arc::new(ConfigFilter)))
+        .add_command("links", Command(Arc::new(ConfigLinks)))
+        .add_command("raid", Command(Arc::new(ConfigRaid)))
         .add_command("register_member", Command(Arc::new(RegisterMember)))
         .add_command("register_cooldown", Command(Arc::new(RegisterCooldown)))
         .add_command("register_duration", Command(Arc::new(RegisterDuration)))
@@ -59,12 +67,19 @@ pub fn init_management(module: ModuleBuilder) -> ModuleBuilder {
     module
         .guild_only(true)
         .help_available(true)
-        // .add_command("setup", Command(Arc::new(SetupMute)))
         .add_command("prune", Command(Arc::new(Prune)))
         .add_command("purge", Alias("prune".to_string()))
         // .add_command("cleanup", Command(Arc::new(Cleanup)))
 }
 
+pub fn init_setup(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .prefix("setup")
+        .add_command("mute", Command(Arc::new(SetupMute)))
+}
+
 pub fn init_roles(module: ModuleBuilder) -> ModuleBuilder {
     module
         .help_available(true)
@@ -74,6 +89,18 @@ pub fn init_roles(module: ModuleBuilder) -> ModuleBuilder {
         .add_command("esr", Command(Arc::new(EditSelfRole)))
 }
 
+pub fn init_schedule(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .help_available(true)
+        .guild_only(true)
+        .prefix("schedule")
+        .default_command(Command(Arc::new(ScheduleList)))
+        .add_command("add", Command(Arc::new(ScheduleAdd)))
+        .add_command("list", Command(Arc::new(ScheduleList)))
+        .add_command("del", Command(Arc::new(ScheduleRemove)))
+        .add_command("remove", Alias("del".to_string()))
+}
+
 // pub fn init_tests(module: ModuleBuilder) -> ModuleBuilder {
 //     module
 //         .guild_only(true)
@@ -0,0 +1,165 @@
+use chrono::Utc;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::recurrence::Recurrence;
+use momiji::core::timers::TimerPayload;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::EmbedBuilder;
+use twilight_mention::Mention;
+use twilight_model::{
+    channel::Message,
+    guild::Permissions,
+};
+use std::error::Error;
+use std::sync::Arc;
+
+pub struct ScheduleAdd;
+#[async_trait]
+impl Command for ScheduleAdd {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Schedule a recurring announcement in a channel. `/every` takes an interval or a cron expression (`minute hour day month weekday`, in UTC); escape `/` in cron steps as `\\/`. `/t` sets when the first announcement is sent, as a relative or absolute time, `/embed` sends it as an embed with an optional `/title`.".to_string()),
+            usage: Some("<channel_resolvable> <content> </every interval_or_cron> [/t time_resolvable] [/embed] [/title title]".to_string()),
+            examples: vec![
+                "#general Remember to read the rules! /every 1w".to_string(),
+                "#events Game night starts in an hour! /every 0 19 * * 5 /embed /title Game Night".to_string(),
+            ],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let channel_id = match parse_channel(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()) {
+                Some((channel_id, _)) => channel_id,
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that channel.")?.await?;
+                    return Ok(());
+                },
+            };
+            let switches = get_switches(args.rest().to_string());
+            let content = switches.get("rest").cloned().unwrap_or(String::new());
+            if content.is_empty() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide something to announce.")?.await?;
+                return Ok(());
+            }
+            let recurrence = match switches.get("every").and_then(|s| Recurrence::parse(s)) {
+                Some(r) => r,
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a schedule with `/every`, either an interval of at least a minute, like `/every 1d`, or a cron expression, like `/every 0 9 * * 1`.")?.await?;
+                    return Ok(());
+                },
+            };
+            let start_time = Utc::now().timestamp();
+            let end_time = match switches.get("t").map(|s| time_to_seconds(s.clone(), user_timezone(message.author.id, &ctx))) {
+                Some(dur) if dur > 0 => Some(start_time + dur),
+                _ => None,
+            };
+            let payload = TimerPayload::Announcement {
+                channel_id,
+                guild_id,
+                content,
+                title: switches.get("title").cloned(),
+                embed: switches.contains_key("embed"),
+            };
+            let timer = ctx.tc.schedule_recurring(payload, &recurrence, start_time, end_time)?;
+            ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Scheduled an announcement in {} {}. The first one will be sent in {}. (ID: {})",
+                channel_id.mention(),
+                recurrence.describe(),
+                seconds_to_hrtime((timer.endtime - start_time) as usize),
+                timer.id
+            ))?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ScheduleList;
+#[async_trait]
+impl Command for ScheduleList {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List the scheduled announcements for this server.".to_string()),
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let now = Utc::now().timestamp();
+            let announcements = ctx.db.get_guild_timers(guild_id.0 as i64)?
+                .iter()
+                .filter_map(|timer| match TimerPayload::from_timer(timer) {
+                    Ok(TimerPayload::Announcement { channel_id, content, embed, .. }) => Some(format!("`{}` in {} {}{}, next in {}: {}",
+                        timer.id,
+                        channel_id.mention(),
+                        match timer.recurrence.as_ref().and_then(|r| Recurrence::parse(r)) {
+                            Some(r) => r.describe(),
+                            None => String::from("once"),
+                        },
+                        if embed { " (embed)" } else { "" },
+                        seconds_to_hrtime(i64::max(timer.endtime - now, 0) as usize),
+                        content)),
+                    _ => None,
+                })
+                .collect::<Vec<String>>();
+
+            if announcements.is_empty() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("There are no scheduled announcements.")?.await?;
+            } else {
+                let embed = EmbedBuilder::new()
+                    .title("Scheduled Announcements")
+                    .color(colors::MAIN)
+                    .description(announcements.join("\n"))
+                    .build()?;
+                ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ScheduleRemove;
+#[async_trait]
+impl Command for ScheduleRemove {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Delete a scheduled announcement. Use `schedule list` to find the ID.".to_string()),
+            usage: Some("<id>".to_string()),
+            examples: vec!["12".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let id = args.single::<i32>().unwrap_or(0);
+            let found = match ctx.db.get_timer(id) {
+                Ok(timer) => timer.guild_id == Some(guild_id.0 as i64)
+                    && matches!(TimerPayload::from_timer(&timer), Ok(TimerPayload::Announcement { .. })),
+                Err(_) => false,
+            };
+            if found {
+                ctx.tc.cancel(id)?;
+                ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Deleted scheduled announcement `{}`.", id))?.await?;
+            } else {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a scheduled announcement with that ID.")?.await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,23 @@
 use chrono::Utc;
+use chrono_tz::Tz;
 use momiji::Context;
 use momiji::core::consts::*;
+use momiji::core::recurrence::Recurrence;
+use momiji::core::timers::TimerPayload;
 use momiji::core::utils::*;
+use momiji::db::models::{Timer, UserUpdate};
 use momiji::framework::args::Args;
 use momiji::framework::command::{Command, Options};
 use std::error::Error;
 use std::sync::Arc;
 use sysinfo::{System, SystemExt, ProcessExt};
-use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder, ImageSource};
+use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource};
 use twilight_model::channel::Message;
 
+const REMINDERS_PER_PAGE: usize = 10;
+/// How much of a reminder's text `remind list` shows, so a full page fits in one embed
+const REMINDER_PREVIEW: usize = 300;
+
 // lazy_static! {
 //     static ref DICE_MATCH: Regex = Regex::new(r"(?P<count>\d+)d?(?P<sides>\d*)").expect("Failed to create Regex");
 // }
@@ -255,44 +263,107 @@ impl Command for BotInfo {
 //     }
 // }
 
-// pub struct Now;
-// impl Command for Now {
-//     fn options(&self) -> Arc<Options> {
-//         let default = Options::default();
-//         let options = Options {
-//             description: Some("Current time. Optionally provide an amount of hours to offset by.".to_string()),
-//             usage: Some("[hour]".to_string()),
-//             example: Some("-5".to_string()),
-//             aliases: vec!["time"].iter().map(|e| e.to_string()).collect(),
-//             ..Options::default()
-//         };
-//         Arc::new(options)
-//     }
+pub struct Now;
+#[async_trait]
+impl Command for Now {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Current time. Shows your own timezone by default, or optionally the time in another timezone or for another user.".to_string()),
+            usage: Some("[timezone|user_resolvable]".to_string()),
+            examples: vec!["America/New_York".to_string(), "@Momiji".to_string()],
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
 
-//     async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
-//         use chrono::offset::FixedOffset;
-//         let utc = Utc::now();
-//         let datetime = match args.single::<i32>() {
-//             Ok(data) => {
-//                 let tz = FixedOffset::east(data * 3600);
-//                 utc.with_timezone(&tz)
-//             },
-//             Err(_) => {
-//                 let tz = FixedOffset::east(0);
-//                 utc.with_timezone(&tz)
-//             },
-//         };
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let input = args.rest().trim().to_string();
+        let tz = if input.is_empty() {
+            user_timezone(message.author.id, &ctx)
+        } else if let Some(tz) = parse_timezone(input.clone()) {
+            tz
+        } else {
+            let user = match message.guild_id {
+                Some(guild_id) => parse_user(input, guild_id, ctx.clone()).await.map(|(user_id, _)| user_id),
+                None => None,
+            };
+            match user {
+                Some(user_id) => user_timezone(user_id, &ctx),
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that timezone or user. Timezones are names like `Europe/Berlin`.")?.await?;
+                    return Ok(());
+                },
+            }
+        };
 
-//         let time = datetime.format("%H:%M").to_string();
-//         let date = datetime.format("%A %e %B %Y").to_string();
-//         message.channel_id.send_message(|m| m
-//             .embed(|e| e
-//                 .colour(*colours::MAIN)
-//                 .descriptionription(format!("**Time:** {}\n**Date:** {}\n**Timezone:** UTC{}", time, date, datetime.timezone()))
-//         ))?;
-//         Ok(())
-//     }
-// }
+        let datetime = Utc::now().with_timezone(&tz);
+        let time = datetime.format("%H:%M").to_string();
+        let date = datetime.format("%A %e %B %Y").to_string();
+        let embed = EmbedBuilder::new()
+            .color(colors::MAIN)
+            .description(format!("**Time:** {}\n**Date:** {}\n**Timezone:** {} (UTC{})", time, date, tz.name(), datetime.format("%:z")))
+            .build()?;
+        ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+
+        Ok(())
+    }
+}
+
+pub struct Timezone;
+#[async_trait]
+impl Command for Timezone {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("View or set your timezone. This is used to resolve absolute times, such as `/t tomorrow 9am` in reminders. Use `reset` to go back to UTC.".to_string()),
+            usage: Some("[timezone|reset]".to_string()),
+            examples: vec!["Europe/Berlin".to_string(), "reset".to_string()],
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let user_id = message.author.id;
+        let input = args.rest().trim().to_string();
+        if input.is_empty() {
+            ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Your timezone is `{}`.", user_timezone(user_id, &ctx).name()))?.await?;
+            return Ok(());
+        }
+
+        let tz = match input.to_lowercase().as_str() {
+            "reset" | "clear" => None,
+            _ => match parse_timezone(input) {
+                Some(tz) => Some(tz),
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("I don't know that timezone. Use a name from the tz database, such as `Europe/Berlin` or `America/New_York`.")?.await?;
+                    return Ok(());
+                },
+            },
+        };
+
+        // Timezones are stored on every users row, so make sure there is at least one
+        if let Some(guild_id) = message.guild_id {
+            ctx.db.upsert_user(UserUpdate {
+                id: user_id.0 as i64,
+                guild_id: guild_id.0 as i64,
+                username: message.author.name.clone(),
+            })?;
+        }
+        let updated = ctx.db.set_user_timezone(user_id.0 as i64, tz.map(|tz| tz.name().to_string()))?;
+
+        if updated == 0 {
+            ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't save your timezone. Try again in a server we share.")?.await?;
+        } else {
+            let tz = tz.unwrap_or(Tz::UTC);
+            ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Your timezone is now `{}`. It's currently {} there.",
+                tz.name(),
+                Utc::now().with_timezone(&tz).format("%H:%M")
+            ))?.await?;
+        }
+
+        Ok(())
+    }
+}
 
 //TODO how to get shard latency here?
 pub struct Ping;
@@ -362,9 +433,9 @@ pub struct Reminder;
 impl Command for Reminder {
     fn options(&self) -> Arc<Options> {
         let options = Options {
-            description: Some("Set a reminder. The reminder is sent to whatever channel it originated in.".to_string()),
-            usage: Some("<reminder text> </t time_resolvable>".to_string()),
-            examples: vec!["do the thing /t 1 day 10 min 25 s".to_string()],
+            description: Some("Set a reminder. The reminder is sent to whatever channel it originated in, or to your DMs with `/dm`. If the channel no longer exists, the reminder is sent by DM. `/t` takes either a relative time or an absolute one such as `at 17:30`, `tomorrow 9am` or `2021-08-01 14:00`, in the timezone set with `timezone`. Use `/every` with an interval or a cron expression (`minute hour day month weekday`, in your timezone) to repeat the reminder.".to_string()),
+            usage: Some("<reminder text> </t time_resolvable | /every interval_or_cron> [/dm]".to_string()),
+            examples: vec!["do the thing /t 1 day 10 min 25 s".to_string(), "take a break /t 2h /dm".to_string(), "call mom /t tomorrow 6pm".to_string(), "drink water /every 2h".to_string(), "stand-up meeting /every 0 9 * * 1-5".to_string()],
             ..Options::default()
         };
         Arc::new(options)
@@ -378,28 +449,198 @@ impl Command for Reminder {
             Some(s) => s.clone(),
             None => String::new(),
         };
+        let dm = switches.contains_key("dm");
         let start_time = Utc::now().timestamp();
-        let dur = hrtime_to_seconds(match switches.get("t") {
+        let tz = user_timezone(user_id, &ctx);
+        let dur = time_to_seconds(match switches.get("t") {
             Some(s) => s.clone(),
             None => String::new(),
-        });
-        if dur>0 {
+        }, tz);
+        if let Some(every) = switches.get("every") {
+            let recurrence = match Recurrence::parse(every) {
+                Some(r) => r.in_timezone(tz),
+                None => {
+                    ctx.http.create_message(channel_id).content("Sorry, I couldn't understand that schedule. Use an interval of at least a minute, like `/every 1d`, or a cron expression, like `/every 0 9 * * 1`.")?.await?;
+                    return Ok(());
+                },
+            };
+            let end_time = if dur>0 { Some(start_time + dur) } else { None };
+            let payload = TimerPayload::Reminder {
+                channel_id,
+                guild_id: message.guild_id,
+                user_id,
+                duration: recurrence.period(start_time).unwrap_or(dur),
+                message: reminder.clone(),
+                dm,
+            };
+            let timer = ctx.tc.schedule_recurring(payload, &recurrence, start_time, end_time)?;
+            ctx.http.create_message(channel_id).content(format!("Got it! I'll remind you{} to {} {}, starting in {}. (ID: {})",
+                if dm { " in your DMs" } else { "" },
+                reminder,
+                recurrence.describe(),
+                seconds_to_hrtime((timer.endtime - start_time) as usize),
+                timer.id
+            ))?.await?;
+        } else if dur>0 {
             let end_time = start_time + dur;
-            let reminder_fmt = format!("REMINDER||{}||{}||{}||{}", channel_id.0, user_id.0, dur, reminder);
-            ctx.db.new_timer(start_time, end_time, reminder_fmt.clone())?;
-            ctx.tc.request();
-            ctx.http.create_message(channel_id).content(format!("Got it! I'll remind you to {} in {}",
+            let payload = TimerPayload::Reminder {
+                channel_id,
+                guild_id: message.guild_id,
+                user_id,
+                duration: dur,
+                message: reminder.clone(),
+                dm,
+            };
+            let timer = ctx.tc.schedule(payload, start_time, end_time)?;
+            ctx.http.create_message(channel_id).content(format!("Got it! I'll remind you{} to {} in {}. (ID: {})",
+                if dm { " in your DMs" } else { "" },
                 reminder,
-                seconds_to_hrtime(dur as usize)
+                seconds_to_hrtime(dur as usize),
+                timer.id
             ))?.await?;
         } else {
-            ctx.http.create_message(channel_id).content("Sorry, I wasn't able to find a time there. Make sure you to add `/t time_resolvable` after your reminder text.")?.await?;
+            ctx.http.create_message(channel_id).content("Sorry, I wasn't able to find a time there. Make sure you to add `/t time_resolvable` after your reminder text, and that absolute times are in the future.")?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ReminderList;
+#[async_trait]
+impl Command for ReminderList {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List your pending reminders.".to_string()),
+            usage: Some("[page]".to_string()),
+            examples: vec!["".to_string(), "2".to_string()],
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let now = Utc::now().timestamp();
+        let reminders = ctx.db.get_user_timers(message.author.id.0 as i64)?
+            .iter()
+            .filter_map(|timer| match TimerPayload::from_timer(timer) {
+                Ok(TimerPayload::Reminder { message, dm, .. }) => Some(format!("`{}` in {}{}{}: {}",
+                    timer.id,
+                    seconds_to_hrtime(i64::max(timer.endtime - now, 0) as usize),
+                    if dm { " (DM)" } else { "" },
+                    match timer.recurrence.as_ref().and_then(|r| Recurrence::parse(r)) {
+                        Some(r) => format!(", repeats {}", r.describe()),
+                        None => String::new(),
+                    },
+                    if message.chars().count() > REMINDER_PREVIEW {
+                        format!("{}…", message.chars().take(REMINDER_PREVIEW - 1).collect::<String>())
+                    } else { message })),
+                _ => None,
+            })
+            .collect::<Vec<String>>();
+
+        if reminders.is_empty() {
+            ctx.http.create_message(message.channel_id).reply(message.id).content("You don't have any pending reminders.")?.await?;
+        } else {
+            let (reminders, page, pages) = paginate(&reminders, args.single::<usize>().unwrap_or(1), REMINDERS_PER_PAGE);
+            let embed = EmbedBuilder::new()
+                .title("Pending Reminders")
+                .color(colors::MAIN)
+                .description(reminders.join("\n"))
+                .footer(EmbedFooterBuilder::new(format!("Page {} of {}", page, pages)))
+                .build()?;
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ReminderCancel;
+#[async_trait]
+impl Command for ReminderCancel {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Cancel one of your pending reminders. Use `remind list` to find the ID.".to_string()),
+            usage: Some("<id>".to_string()),
+            examples: vec!["42".to_string()],
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let id = args.single::<i32>().unwrap_or(0);
+        match get_own_reminder(id, &message, &ctx) {
+            Some((_, reminder)) => {
+                ctx.tc.cancel(id)?;
+                ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Cancelled reminder `{}`: {}", id, reminder))?.await?;
+            },
+            None => {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a pending reminder of yours with that ID.")?.await?;
+            },
+        }
+
+        Ok(())
+    }
+}
+
+pub struct ReminderSnooze;
+#[async_trait]
+impl Command for ReminderSnooze {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Push back one of your pending reminders. Defaults to 10 minutes.".to_string()),
+            usage: Some("<id> [/t time_resolvable]".to_string()),
+            examples: vec!["42 /t 1h".to_string()],
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let id = args.single::<i32>().unwrap_or(0);
+        let switches = get_switches(args.rest().to_string());
+        let dur = match switches.get("t") {
+            Some(s) => hrtime_to_seconds(s.clone()),
+            None => 10 * MIN as i64,
+        };
+        if dur <= 0 {
+            ctx.http.create_message(message.channel_id).reply(message.id).content("Sorry, I wasn't able to find a time there.")?.await?;
+            return Ok(());
+        }
+        match get_own_reminder(id, &message, &ctx) {
+            Some((timer, _)) => {
+                let mut payload = TimerPayload::from_timer(&timer)?;
+                if let TimerPayload::Reminder { ref mut duration, .. } = payload {
+                    *duration += dur;
+                }
+                let end_time = i64::max(timer.endtime, Utc::now().timestamp()) + dur;
+                let timer = ctx.tc.reschedule(timer, payload, end_time)?;
+                ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Snoozed reminder `{}`. I'll remind you in {}.",
+                    timer.id,
+                    seconds_to_hrtime((end_time - Utc::now().timestamp()) as usize)
+                ))?.await?;
+            },
+            None => {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a pending reminder of yours with that ID.")?.await?;
+            },
         }
 
         Ok(())
     }
 }
 
+/// Fetches a pending reminder by ID, provided it belongs to the author of the message
+fn get_own_reminder(id: i32, message: &Message, ctx: &Context) -> Option<(Timer, String)> {
+    let timer = ctx.db.get_timer(id).ok()?;
+    if timer.owner_id != Some(message.author.id.0 as i64) || timer.failed { return None; }
+    match TimerPayload::from_timer(&timer) {
+        Ok(TimerPayload::Reminder { message, .. }) => Some((timer, message)),
+        _ => None,
+    }
+}
+
 // pub struct RoleInfo;
 // impl Command for RoleInfo {
 //     fn options(&self) -> Arc<Options> {
@@ -20,10 +20,10 @@ pub fn init_misc(module: ModuleBuilder) -> ModuleBuilder {
         // .add_command("dog", Command(Arc::new(Dog)))
         // .add_command("joke", Command(Arc::new(DadJoke)))
         // .add_command("manga", Command(Arc::new(Manga)))
-        // .add_command("now", Command(Arc::new(Now)))
+        .add_command("now", Command(Arc::new(Now)))
+        .add_command("time", Alias("now".to_string()))
         .add_command("ping", Command(Arc::new(Ping)))
         .add_command("prefix", Command(Arc::new(Prefix)))
-        .add_command("remind", Command(Arc::new(Reminder)))
         // .add_command("roleinfo", Command(Arc::new(RoleInfo)))
         // .add_command("roll", Command(Arc::new(Roll)))
         // .add_command("serverinfo", Command(Arc::new(ServerInfo)))
@@ -33,6 +33,8 @@ pub fn init_misc(module: ModuleBuilder) -> ModuleBuilder {
         // .add_command("userinfo", Command(Arc::new(UserInfo)))
         // .add_command("weather", Command(Arc::new(Weather)))
         // .add_command("stats", Command(Arc::new(Stats)))
+        .add_command("timezone", Command(Arc::new(Timezone)))
+        .add_command("tz", Alias("timezone".to_string()))
 }
 
 // pub fn init_nsfw() -> CreateGroup {
@@ -53,6 +55,17 @@ pub fn init_misc(module: ModuleBuilder) -> ModuleBuilder {
 //         .cmd("e621", Furry)
 // }
 
+pub fn init_reminders(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .help_available(true)
+        .prefix("remind")
+        .default_command(Command(Arc::new(Reminder)))
+        .add_command("add", Command(Arc::new(Reminder)))
+        .add_command("list", Command(Arc::new(ReminderList)))
+        .add_command("cancel", Command(Arc::new(ReminderCancel)))
+        .add_command("snooze", Command(Arc::new(ReminderSnooze)))
+}
+
 pub fn init_roles(module: ModuleBuilder) -> ModuleBuilder {
     module
         .help_available(true)
@@ -0,0 +1,103 @@
+use momiji::Context;
+use momiji::core::antiraid;
+use momiji::core::consts::*;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::EmbedBuilder;
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use std::error::Error;
+use std::sync::Arc;
+
+pub struct AntiraidStatus;
+#[async_trait]
+impl Command for AntiraidStatus {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Show the raid protection settings and whether a raid is currently being handled.".to_string()),
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let locks = ctx.db.get_channel_locks(guild_id.0 as i64)?.into_iter().filter(|l| l.raid).count();
+
+            let embed = EmbedBuilder::new()
+                .title("Raid Protection")
+                .color(if guild_data.raid_active { colors::RED } else { colors::MAIN })
+                .description(format!("**Status:** {}\n**Threshold:** {}\n**New Account Age:** {}\n**Action:** {}\n**Channels:** {}\n**Punishment:** {}\n**Locked Channels:** {}",
+                    if guild_data.raid_active { "Raid in progress, use `antiraid off` to lift" } else { "Watching" },
+                    if guild_data.raid_joins > 0 { format!("{} joins in {}s", guild_data.raid_joins, guild_data.raid_seconds) } else { String::from("Off") },
+                    if guild_data.raid_account_age > 0 { seconds_to_hrtime(guild_data.raid_account_age as usize) } else { String::from("Off") },
+                    guild_data.raid_action,
+                    if guild_data.raid_channels.is_empty() { String::from("All text channels") } else { guild_data.raid_channels.iter().map(|id| format!("<#{}>", id)).collect::<Vec<String>>().join(", ") },
+                    guild_data.raid_punish,
+                    locks,
+                ))
+                .build()?;
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct AntiraidOn;
+#[async_trait]
+impl Command for AntiraidOn {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Manually trigger raid protection, taking the action set with `config raid`.".to_string()),
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            if guild_data.raid_active {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("Raid protection is already active.")?.await?;
+                return Ok(())
+            }
+            antiraid::start(&ctx, guild_id, &[]).await?;
+            ctx.http.create_message(message.channel_id).reply(message.id).content("Raid protection is now active. Use `antiraid off` to lift it.")?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct AntiraidOff;
+#[async_trait]
+impl Command for AntiraidOff {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Lift raid protection, restoring the verification level and unlocking any channels it locked.".to_string()),
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            if antiraid::stop(&ctx, guild_id).await? {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("Raid protection lifted.")?.await?;
+            } else {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("Raid protection isn't active.")?.await?;
+            }
+        }
+
+        Ok(())
+    }
+}
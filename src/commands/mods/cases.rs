@@ -0,0 +1,207 @@
+use chrono::Utc;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::moderation;
+use momiji::core::utils::*;
+use momiji::db::models::Case;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use tracing::{event, Level};
+use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, MessageId, UserId};
+use std::error::Error;
+use std::sync::Arc;
+
+/// How many cases are shown per page of `case list`
+const CASES_PER_PAGE: usize = 10;
+
+pub struct CaseShow;
+#[async_trait]
+impl Command for CaseShow {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Show a case by its number.".to_string()),
+            usage: Some("<case number>".to_string()),
+            examples: vec!["12".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let number = args.single::<i32>().unwrap_or(0);
+            match ctx.db.get_case(guild_id.0 as i64, number) {
+                Ok(case) => {
+                    let mut description = format!("**User:** {}\n**Moderator:** {}\n**Reason:** {}",
+                        format_user(case.user_id, &ctx),
+                        format_user(case.moderator, &ctx),
+                        if case.reason.is_empty() { "No reason given" } else { case.reason.as_str() });
+                    if let (Some(channel), Some(log)) = (case.modlog_channel, case.modlog_message) {
+                        description.push_str(format!("\n[Modlog entry](https://discord.com/channels/{}/{}/{})", guild_id.0, channel, log).as_str());
+                    }
+                    let embed = EmbedBuilder::new()
+                        .title(format!("Case {}: {}", case.case_number, case.casetype))
+                        .color(colors::MAIN)
+                        .description(description)
+                        .timestamp(case.timestamp.to_rfc3339())
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+                },
+                Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a case with that number.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CaseList;
+#[async_trait]
+impl Command for CaseList {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List cases, newest first. Can be narrowed down to a single user.".to_string()),
+            usage: Some("[user_resolvable] [page]".to_string()),
+            examples: vec!["".to_string(), "2".to_string(), "@Adelyn".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            // A bare number is a page, anything else is a user
+            let (cases, title) = match args.single_n::<usize>() {
+                Err(_) if !args.is_empty() => {
+                    match resolve_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                        Some((user, member)) => (
+                            ctx.db.get_cases(user.id.0 as i64, guild_id.0 as i64)?,
+                            format!("Cases for {}", member.and_then(|m| m.nick.clone()).unwrap_or(user.name)),
+                        ),
+                        None => {
+                            ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?;
+                            return Ok(());
+                        },
+                    }
+                },
+                _ => (ctx.db.get_guild_cases(guild_id.0 as i64)?, String::from("Cases")),
+            };
+            let (cases, page, pages) = paginate(&cases, args.single::<usize>().unwrap_or(1), CASES_PER_PAGE);
+            let cases_fmt = if cases.is_empty() {
+                String::from("No cases")
+            } else {
+                cases.iter().map(|c| format_case(c, &ctx)).collect::<Vec<String>>().join("\n")
+            };
+            let embed = EmbedBuilder::new()
+                .color(colors::MAIN)
+                .title(title)
+                .description(cases_fmt)
+                .footer(EmbedFooterBuilder::new(format!("Page {} of {}", page, pages)))
+                .build()?;
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CaseReason;
+#[async_trait]
+impl Command for CaseReason {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the reason of a case. The case's modlog entry is updated too.".to_string()),
+            usage: Some("<case number> <reason>".to_string()),
+            examples: vec!["12 spamming invites".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let number = args.single::<i32>().unwrap_or(0);
+            let reason = args.rest().trim().to_string();
+            if reason.is_empty() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a reason.")?.await?;
+                return Ok(());
+            }
+            match ctx.db.update_case_reason(guild_id.0 as i64, number, reason.clone()) {
+                Ok(case) => {
+                    if let (Some(channel), Some(log)) = (case.modlog_channel, case.modlog_message) {
+                        let (channel, log) = (ChannelId(channel as u64), MessageId(log as u64));
+                        match ctx.http.message(channel, log).await {
+                            Ok(Some(entry)) => if let Some(embed) = entry.embeds.first() {
+                                let embed = moderation::replace_reason(embed.clone(), reason.as_str());
+                                ctx.http.update_message(channel, log).embed(embed)?.await?;
+                            },
+                            Ok(None) => {},
+                            Err(why) => { event!(Level::DEBUG, "Unable to fetch modlog entry for case {} in {}: {}", case.case_number, guild_id, why); },
+                        }
+                    }
+                    ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Updated the reason for case {}.", case.case_number))?.await?;
+                },
+                Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a case with that number.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct CaseRemove;
+#[async_trait]
+impl Command for CaseRemove {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Delete a case. Its modlog entry is left in place.".to_string()),
+            usage: Some("<case number>".to_string()),
+            examples: vec!["12".to_string()],
+            required_permissions: Permissions::MANAGE_GUILD,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let number = args.single::<i32>().unwrap_or(0);
+            match ctx.db.del_case(guild_id.0 as i64, number) {
+                Ok(case) => { ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Deleted case {} ({}).", case.case_number, case.casetype))?.await?; },
+                Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a case with that number.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats a user by ID, using the cache where possible
+fn format_user(id: i64, ctx: &Context) -> String {
+    match ctx.cache.user(UserId(id as u64)) {
+        Some(user) => format!("{}#{} ({})", user.name, user.discriminator, id),
+        None if id > 0 => format!("<@{}> ({})", id, id),
+        None => String::from("Unknown"),
+    }
+}
+
+/// A one line summary of a case for `case list`
+fn format_case(case: &Case<Utc>, ctx: &Context) -> String {
+    format!("`#{}` **{}** {} by {} on {}{}",
+        case.case_number,
+        case.casetype,
+        format_user(case.user_id, ctx),
+        format_user(case.moderator, ctx),
+        case.timestamp.format("%F"),
+        if case.reason.is_empty() { String::new() } else { format!(": {}", case.reason) })
+}
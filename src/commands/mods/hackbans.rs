@@ -0,0 +1,179 @@
+use chrono::Utc;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::moderation;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use tracing::{event, Level};
+use twilight_embed_builder::EmbedBuilder;
+use twilight_http::request::AuditLogReason;
+use twilight_model::channel::{Message, embed::Embed};
+use twilight_model::guild::Permissions;
+use twilight_model::id::UserId;
+use std::error::Error;
+use std::sync::Arc;
+
+pub struct HackbanAdd;
+#[async_trait]
+impl Command for HackbanAdd {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Ban a user who isn't in the server by ID. The ban is applied right away where possible, otherwise they are banned as soon as they join.".to_string()),
+            usage: Some("<user_id> [/r reason]".to_string()),
+            examples: vec!["242675474927583232 /r raider".to_string()],
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let user_id = match parse_user_id(args.single::<String>().unwrap_or(String::new())) {
+                Some(id) => id,
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a valid user ID.")?.await?;
+                    return Ok(());
+                },
+            };
+            if ctx.cache.member(guild_id, user_id).is_some() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("That user is in the server. Use `ban` instead.")?.await?;
+                return Ok(());
+            }
+            let reason = get_switches(args.rest().to_string())
+                .get("r")
+                .cloned()
+                .filter(|r| !r.is_empty());
+            if ctx.db.new_hackban(user_id.0 as i64, guild_id.0 as i64, reason.clone()).is_err() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("That user is already hackbanned.")?.await?;
+                return Ok(());
+            }
+
+            let audit_reason = format!("Hackban by {}#{}: {}", message.author.name, message.author.discriminator, reason.clone().unwrap_or("No reason given".to_string()));
+            let applied = match ctx.http.create_ban(guild_id, user_id).reason(audit_reason)?.await {
+                Ok(_) => true,
+                Err(why) => {
+                    event!(Level::DEBUG, "Unable to apply hackban for {} in {}: {}", user_id, guild_id, why);
+                    false
+                },
+            };
+
+            let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let embed = hackban_embed("User Hackbanned", colors::RED, user_id, &message, &reason, &ctx).await?;
+            moderation::send_modlog(&ctx, &guild_data, message.channel_id, embed).await?;
+            if guild_data.modlog && guild_data.modlog_channel > 0 {
+                ctx.http.create_message(message.channel_id).reply(message.id).content(if applied {
+                    format!("Banned {}.", user_id.0)
+                } else {
+                    format!("Added {} to the hackban list. They will be banned if they join.", user_id.0)
+                })?.await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct HackbanRemove;
+#[async_trait]
+impl Command for HackbanRemove {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Remove a user from the hackban list and lift their ban.".to_string()),
+            usage: Some("<user_id>".to_string()),
+            examples: vec!["242675474927583232".to_string()],
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let user_id = match parse_user_id(args.single::<String>().unwrap_or(String::new())) {
+                Some(id) => id,
+                None => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a valid user ID.")?.await?;
+                    return Ok(());
+                },
+            };
+            match ctx.db.del_hackban(user_id.0 as i64, guild_id.0 as i64) {
+                Ok(hackban) => {
+                    if let Err(why) = ctx.http.delete_ban(guild_id, user_id).await {
+                        event!(Level::DEBUG, "Unable to lift hackban for {} in {}: {}", user_id, guild_id, why);
+                    }
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    let embed = hackban_embed("Hackban Removed", colors::GREEN, user_id, &message, &hackban.reason, &ctx).await?;
+                    moderation::send_modlog(&ctx, &guild_data, message.channel_id, embed).await?;
+                    if guild_data.modlog && guild_data.modlog_channel > 0 {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Removed {} from the hackban list.", user_id.0))?.await?;
+                    }
+                },
+                Err(_) => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("That user isn't hackbanned.")?.await?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct HackbanList;
+#[async_trait]
+impl Command for HackbanList {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List hackbanned users.".to_string()),
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let hackbans = ctx.db.get_hackbans(guild_id.0 as i64)?;
+            if hackbans.is_empty() {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("There are no hackbanned users.")?.await?;
+            } else {
+                let list = hackbans.iter()
+                    .map(|h| format!("{}: {}", h.id, h.reason.clone().unwrap_or("No reason given".to_string())))
+                    .collect::<Vec<String>>()
+                    .join("\n");
+                let embed = EmbedBuilder::new()
+                    .title("Hackbans")
+                    .color(colors::MAIN)
+                    .description(list)
+                    .build()?;
+                ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+async fn hackban_embed(title: &str, color: u32, user_id: UserId, message: &Message, reason: &Option<String>, ctx: &Context) -> Result<Embed, Box<dyn Error + Send + Sync>> {
+    let user = match ctx.http.user(user_id).await {
+        Ok(Some(user)) => format!("{}#{} ({})", user.name, user.discriminator, user_id.0),
+        _ => user_id.0.to_string(),
+    };
+    let embed = EmbedBuilder::new()
+        .title(title)
+        .color(color)
+        .timestamp(Utc::now().to_rfc3339())
+        .description(format!("**User:** {}\n**Moderator:** {}#{} ({})\n**Reason:** {}",
+            user,
+            message.author.name,
+            message.author.discriminator,
+            message.author.id.0,
+            reason.clone().unwrap_or("No reason given".to_string())))
+        .build()?;
+
+    Ok(embed)
+}
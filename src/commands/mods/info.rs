@@ -1,49 +1,139 @@
-use crate::core::consts::*;
-use crate::core::consts::DB as db;
-use crate::core::utils::*;
-use serenity::framework::standard::{
-    Args,
-    Command,
-    CommandError,
-    CommandOptions
-};
-use serenity::model::channel::Message;
-use serenity::model::Permissions;
-use serenity::prelude::Context;
+use chrono::{DateTime, Utc};
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::{EmbedBuilder, EmbedFieldBuilder, EmbedFooterBuilder, ImageSource};
+use twilight_mention::Mention;
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use std::collections::BTreeMap;
+use std::error::Error;
 use std::sync::Arc;
 
+/// How many notes are shown per page of `modinfo`
+const NOTES_PER_PAGE: usize = 3;
+/// How many of the latest cases are listed below the summary
+const RECENT_CASES: usize = 3;
+/// The longest an embed field value may be
+const FIELD_LIMIT: usize = 1024;
+
 pub struct ModInfo;
+#[async_trait]
 impl Command for ModInfo {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("View some useful information on a user.".to_string()),
-            usage: Some("<user_resolvable>".to_string()),
-            example: Some("@Adelyn".to_string()),
-            aliases: vec!["mi", "minfo"].iter().map(|e| e.to_string()).collect(),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("View a user's moderation profile: account and join dates, stored data, case history, notes and roles. Notes are paginated.".to_string()),
+            usage: Some("<user_resolvable> [page]".to_string()),
+            examples: vec!["@Adelyn".to_string(), "@Adelyn 2".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
-                Some((user_id, _)) => {
-                    let user = db.get_user(user_id.0 as i64, guild_id.0 as i64)?;
-                    let cases = db.get_cases(user_id.0 as i64, guild_id.0 as i64)?;
-                    let case_fmt = cases.iter().map(|c| format!("Type: {}\nModerator: {}\nTimestamp: {}", c.casetype, c.moderator, c.timestamp)).collect::<Vec<String>>().join("\n");
-                    message.channel_id.send_message(|m| m
-                        .embed(|e| e
-                            .title("Moderator info")
-                            .field("Watchlist", { if user.watchlist { "Yes" } else { "No" } }, false)
-                            .field("Cases", if case_fmt.is_empty() { "None" } else { case_fmt.as_str() }, false)
-                    ))?;
+            match resolve_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user, member)) => {
+                    let user_id = user.id;
+                    let now = Utc::now();
+                    let created = snowflake_time(user_id.0);
+                    let joined = match &member {
+                        Some(member) => member.joined_at.as_ref()
+                            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                            .map(|t| format_date(t.with_timezone(&Utc), now))
+                            .unwrap_or("Unknown".to_string()),
+                        None => String::from("Not in the server"),
+                    };
+
+                    let stored = match ctx.db.get_user(user_id.0 as i64, guild_id.0 as i64) {
+                        Ok(data) => format!("**Registered:** {}\n**Last Message:** {}\n**Watchlist:** {}\n**Stored Nickname:** {}",
+                            data.registered.map(|t| format_date(t, now)).unwrap_or("No".to_string()),
+                            format_date(data.last_message, now),
+                            if data.watchlist { "Yes" } else { "No" },
+                            if data.nickname.is_empty() { "None" } else { data.nickname.as_str() }),
+                        Err(_) => String::from("No stored data"),
+                    };
+
+                    let cases = ctx.db.get_cases(user_id.0 as i64, guild_id.0 as i64)?;
+                    let mut summary: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+                    for case in cases.iter() {
+                        let entry = summary.entry(case.casetype.as_str()).or_insert((0, 0));
+                        entry.0 += 1;
+                        if case.pardoned { entry.1 += 1; }
+                    }
+                    let cases_fmt = if cases.is_empty() {
+                        String::from("None")
+                    } else {
+                        let totals = summary.iter()
+                            .map(|(casetype, (total, pardoned))| if *pardoned > 0 {
+                                format!("{}: {} ({} pardoned)", casetype, total, pardoned)
+                            } else {
+                                format!("{}: {}", casetype, total)
+                            })
+                            .collect::<Vec<String>>()
+                            .join(", ");
+                        let recent = cases.iter()
+                            .take(RECENT_CASES)
+                            .map(|c| format!("`#{}` {} on {}", c.case_number, c.casetype, c.timestamp.format("%F")))
+                            .collect::<Vec<String>>()
+                            .join("\n");
+                        format!("{}\n{}", totals, recent)
+                    };
+
+                    let mut notes = ctx.db.get_notes(user_id.0 as i64, guild_id.0 as i64)?;
+                    notes.sort_by(|a, b| b.id.cmp(&a.id));
+                    let (notes, page, pages) = paginate(&notes, args.single::<usize>().unwrap_or(1), NOTES_PER_PAGE);
+                    let notes_fmt = if notes.is_empty() {
+                        String::from("None")
+                    } else {
+                        notes.iter().map(|n| n.to_string()).collect::<Vec<String>>().join("\n")
+                    };
+
+                    let roles = member.map(|m| m.roles.clone()).unwrap_or_default()
+                        .iter()
+                        .filter_map(|id| ctx.cache.role(*id))
+                        .map(|role| role.id.mention().to_string())
+                        .collect::<Vec<String>>();
+
+                    let embed = EmbedBuilder::new()
+                        .title(format!("Moderation info for {}#{}", user.name, user.discriminator))
+                        .color(colors::MAIN)
+                        .thumbnail(ImageSource::url(user_avatar_url(&user))?)
+                        .description(format!("{} ({})", user.mention(), user_id.0))
+                        .field(EmbedFieldBuilder::new("Account Created", format_date(created, now)).inline())
+                        .field(EmbedFieldBuilder::new("Joined", joined).inline())
+                        .field(EmbedFieldBuilder::new("Stored Data", stored))
+                        .field(EmbedFieldBuilder::new(format!("Cases ({})", cases.len()), truncate(cases_fmt)))
+                        .field(EmbedFieldBuilder::new("Notes", truncate(notes_fmt)))
+                        .field(EmbedFieldBuilder::new(format!("Roles ({})", roles.len()), truncate(if roles.is_empty() { String::from("None") } else { roles.join(" ") })))
+                        .footer(EmbedFooterBuilder::new(format!("Page {} of {}", page, pages)))
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
                 },
-                None => { message.channel_id.say("I couldn't find that user.")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user.")?.await?; },
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Formats a date along with how long ago it was
+fn format_date(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = (now - time).num_seconds();
+    format!("{}\n{} ago",
+        time.format("%a, %d %h %Y @ %H:%M"),
+        if elapsed < 60 { String::from("less than a minute") } else { seconds_to_hrtime((elapsed - elapsed % 60) as usize) })
+}
+
+/// Cuts a field value down to the embed field limit
+fn truncate(value: String) -> String {
+    if value.chars().count() <= FIELD_LIMIT { return value; }
+    let mut value = value.chars().take(FIELD_LIMIT - 1).collect::<String>();
+    value.push('…');
+    value
+}
@@ -0,0 +1,147 @@
+use momiji::Context;
+use momiji::core::moderation::{self, BanKind};
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use std::error::Error;
+use std::sync::Arc;
+
+pub struct KickUser;
+#[async_trait]
+impl Command for KickUser {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Kick a user. Can provide an optional reason.".to_string()),
+            usage: Some("<user_resolvable> [/r reason]".to_string()),
+            examples: vec!["@Adelyn /r spam".to_string()],
+            required_permissions: Permissions::KICK_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user_id, member)) => {
+                    if !moderation::can_moderate(guild_id, message.author.id, user_id, &ctx) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("You can't kick someone at or above your highest role.")?.await?;
+                        return Ok(());
+                    }
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    let reason = get_switches(args.rest().to_string())
+                        .get("r")
+                        .cloned()
+                        .filter(|r| !r.is_empty());
+                    moderation::kick(&ctx, &guild_data, guild_id, &member.user, &message.author, reason, message.channel_id).await?;
+                },
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct BanUser;
+#[async_trait]
+impl Command for BanUser {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Ban a user. Can provide an optional reason and a number of days of messages to delete, up to 7. Users who aren't in the server can be banned by ID.".to_string()),
+            usage: Some("<user_resolvable> [/d days] [/r reason]".to_string()),
+            examples: vec!["@Adelyn /d 1 /r spam".to_string()],
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        run_ban(message, args, ctx, BanKind::Ban).await
+    }
+}
+
+pub struct SoftbanUser;
+#[async_trait]
+impl Command for SoftbanUser {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Ban and immediately unban a user, deleting their recent messages. Deletes 1 day of messages unless `/d` is given.".to_string()),
+            usage: Some("<user_resolvable> [/d days] [/r reason]".to_string()),
+            examples: vec!["@Adelyn /r spam".to_string()],
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        run_ban(message, args, ctx, BanKind::Softban).await
+    }
+}
+
+pub struct TempbanUser;
+#[async_trait]
+impl Command for TempbanUser {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Ban a user for a limited time. They are unbanned automatically once the time is up.".to_string()),
+            usage: Some("<user_resolvable> </t time> [/d days] [/r reason]".to_string()),
+            examples: vec!["@Adelyn /t 3d /r spam".to_string()],
+            required_permissions: Permissions::BAN_MEMBERS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        run_ban(message, args, ctx, BanKind::Tempban(0)).await
+    }
+}
+
+/// Shared implementation of ban, softban and tempban. The duration of a tempban is read from `/t`.
+async fn run_ban(message: Message, mut args: Args, ctx: Context, kind: BanKind) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if let Some(guild_id) = message.guild_id {
+        let user = match resolve_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+            Some((user, _)) => user,
+            None => {
+                ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user.")?.await?;
+                return Ok(());
+            },
+        };
+        if ctx.cache.member(guild_id, user.id).is_some() && !moderation::can_moderate(guild_id, message.author.id, user.id, &ctx) {
+            ctx.http.create_message(message.channel_id).reply(message.id).content("You can't ban someone at or above your highest role.")?.await?;
+            return Ok(());
+        }
+
+        let switches = get_switches(args.rest().to_string());
+        let kind = match kind {
+            BanKind::Tempban(_) => match switches.get("t").map(|s| hrtime_to_seconds(s.clone())) {
+                Some(time) if time > 0 => BanKind::Tempban(time),
+                _ => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a duration with `/t`.")?.await?;
+                    return Ok(());
+                },
+            },
+            kind => kind,
+        };
+        let delete_days = switches.get("d")
+            .and_then(|s| s.parse::<u64>().ok())
+            .unwrap_or(if kind == BanKind::Softban { 1 } else { 0 });
+        let reason = switches.get("r")
+            .cloned()
+            .filter(|r| !r.is_empty());
+
+        let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+        moderation::ban(&ctx, &guild_data, guild_id, &user, &message.author, kind, delete_days, reason, message.channel_id).await?;
+    }
+
+    Ok(())
+}
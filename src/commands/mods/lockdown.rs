@@ -0,0 +1,112 @@
+use momiji::Context;
+use momiji::core::lockdown;
+use momiji::core::moderation;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::ChannelId;
+use std::error::Error;
+use std::sync::Arc;
+
+pub struct Lock;
+#[async_trait]
+impl Command for Lock {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Lock a channel, stopping @everyone from sending messages. Defaults to the current channel, `all` locks every text channel. Can provide an optional time after which the channel is unlocked, and a reason.".to_string()),
+            usage: Some("[channel_resolvable|all] [/t time] [/r reason]".to_string()),
+            examples: vec!["#general /t 30m /r heated argument".to_string(), "all /r raid".to_string()],
+            required_permissions: Permissions::MANAGE_CHANNELS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let switches = get_switches(args.rest().to_string());
+            let target = switches.get("rest").cloned().unwrap_or(String::new()).trim().to_string();
+            let channels = match target.to_lowercase().as_str() {
+                "" => vec![message.channel_id],
+                "all" => lockdown::text_channels(guild_id, &ctx),
+                _ => match parse_channel(target.clone(), guild_id, ctx.clone()) {
+                    Some((channel_id, _)) => vec![channel_id],
+                    None => {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that channel.")?.await?;
+                        return Ok(())
+                    },
+                },
+            };
+            let duration = switches.get("t")
+                .map(|s| hrtime_to_seconds(s.clone()))
+                .filter(|t| *t > 0);
+            let reason = switches.get("r")
+                .cloned()
+                .filter(|r| !r.is_empty());
+            let locked = moderation::lock(&ctx, &guild_data, guild_id, &channels, &message.author, duration, reason, message.channel_id).await?;
+            let content = match (locked.len(), duration) {
+                (0, _) if channels.len() == 1 => String::from("That channel is already locked."),
+                (0, _) => String::from("There were no channels to lock."),
+                (count, Some(duration)) => format!("Locked {} channel{} for {}.", count, if count == 1 { "" } else { "s" }, seconds_to_hrtime(duration as usize)),
+                (count, None) => format!("Locked {} channel{}.", count, if count == 1 { "" } else { "s" }),
+            };
+            ctx.http.create_message(message.channel_id).reply(message.id).content(content)?.await?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Unlock;
+#[async_trait]
+impl Command for Unlock {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Unlock a channel, putting back the permissions it had before it was locked. Defaults to the current channel, `all` unlocks every locked channel.".to_string()),
+            usage: Some("[channel_resolvable|all] [/r reason]".to_string()),
+            examples: vec!["#general".to_string(), "all /r raid over".to_string()],
+            required_permissions: Permissions::MANAGE_CHANNELS,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+            let switches = get_switches(args.rest().to_string());
+            let target = switches.get("rest").cloned().unwrap_or(String::new()).trim().to_string();
+            let channels = match target.to_lowercase().as_str() {
+                "" => vec![message.channel_id],
+                "all" => ctx.db.get_channel_locks(guild_id.0 as i64)?
+                    .iter()
+                    .map(|lock| ChannelId(lock.channel_id as u64))
+                    .collect(),
+                _ => match parse_channel(target.clone(), guild_id, ctx.clone()) {
+                    Some((channel_id, _)) => vec![channel_id],
+                    None => {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that channel.")?.await?;
+                        return Ok(())
+                    },
+                },
+            };
+            let reason = switches.get("r")
+                .cloned()
+                .filter(|r| !r.is_empty());
+            let unlocked = moderation::unlock(&ctx, &guild_data, guild_id, &channels, &message.author, reason, message.channel_id).await?;
+            let content = match unlocked.len() {
+                0 if channels.len() == 1 => String::from("That channel isn't locked."),
+                0 => String::from("There are no locked channels."),
+                count => format!("Unlocked {} channel{}.", count, if count == 1 { "" } else { "s" }),
+            };
+            ctx.http.create_message(message.channel_id).reply(message.id).content(content)?.await?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,53 +1,111 @@
-// pub mod hackbans;
-// pub mod info;
-// pub mod kickbans;
-// pub mod mute;
-// pub mod notes;
+pub mod antiraid;
+pub mod cases;
+pub mod hackbans;
+pub mod info;
+pub mod kickbans;
+pub mod lockdown;
+pub mod mute;
+pub mod notes;
 pub mod roles;
-// pub mod watchlist;
+pub mod warnings;
+pub mod watchlist;
 
-// use self::hackbans::*;
-// use self::info::*;
-// use self::kickbans::*;
-// use self::mute::*;
-// use self::notes::*;
+use self::antiraid::*;
+use self::cases::*;
+use self::hackbans::*;
+use self::info::*;
+use self::kickbans::*;
+use self::lockdown::*;
+use self::mute::*;
+use self::notes::*;
 use self::roles::*;
-// use self::watchlist::*;
+use self::warnings::*;
+use self::watchlist::*;
 use momiji::framework::command::{CommandOrAlias::*, ModuleBuilder};
 use std::sync::Arc;
 
-// pub fn init_info(module: ModuleBuilder) -> ModuleBuilder {
-//     module
-//         .guild_only(true)
-//         .help_available(true)
-//         .add_command("modinfo", Command(Arc::new(ModInfo)))
-// }
+pub fn init_antiraid(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .prefix("antiraid")
+        .guild_only(true)
+        .help_available(true)
+        .default_command(Command(Arc::new(AntiraidStatus)))
+        .add_command("status", Command(Arc::new(AntiraidStatus)))
+        .add_command("on", Command(Arc::new(AntiraidOn)))
+        .add_command("off", Command(Arc::new(AntiraidOff)))
+}
+
+pub fn init_cases(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .prefix("case")
+        .guild_only(true)
+        .help_available(true)
+        .default_command(Command(Arc::new(CaseShow)))
+        .add_command("show", Command(Arc::new(CaseShow)))
+        .add_command("list", Command(Arc::new(CaseList)))
+        .add_command("reason", Command(Arc::new(CaseReason)))
+        .add_command("delete", Command(Arc::new(CaseRemove)))
+        .add_command("del", Alias("delete".to_string()))
+}
+
+pub fn init_hackbans(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .prefix("hackban")
+        .guild_only(true)
+        .help_available(true)
+        .default_command(Command(Arc::new(HackbanList)))
+        .add_command("add", Command(Arc::new(HackbanAdd)))
+        .add_command("remove", Command(Arc::new(HackbanRemove)))
+        .add_command("del", Alias("remove".to_string()))
+        .add_command("list", Command(Arc::new(HackbanList)))
+}
+
+pub fn init_info(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .add_command("modinfo", Command(Arc::new(ModInfo)))
+        .add_command("mi", Alias("modinfo".to_string()))
+        .add_command("minfo", Alias("modinfo".to_string()))
+}
+
+pub fn init_kickbans(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .add_command("ban", Command(Arc::new(BanUser)))
+        .add_command("kick", Command(Arc::new(KickUser)))
+        .add_command("softban", Command(Arc::new(SoftbanUser)))
+        .add_command("tempban", Command(Arc::new(TempbanUser)))
+}
 
-// pub fn init_kickbans(module: ModuleBuilder) -> ModuleBuilder {
-//     module
-//         .guild_only(true)
-//         .help_available(true)
-//         .add_command("ban", Command(Arc::new(BanUser)))
-//         .add_command("kick", Command(Arc::new(KickUser)))
-// }
+pub fn init_lockdown(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .add_command("lock", Command(Arc::new(Lock)))
+        .add_command("unlock", Command(Arc::new(Unlock)))
+}
 
-// pub fn init_mute(module: ModuleBuilder) -> ModuleBuilder {
-//     module
-//         .guild_only(true)
-//         .help_available(true)
-//         .add_command("mute", Command(Arc::new(Mute)))
-//         .add_command("unmute", Command(Arc::new(Unmute)))
-// }
+pub fn init_mute(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .add_command("mute", Command(Arc::new(Mute)))
+        .add_command("unmute", Command(Arc::new(Unmute)))
+}
 
-// pub fn init_notes(module: ModuleBuilder) -> ModuleBuilder {
-//     module
-//         .prefix("note")
-//         .guild_only(true)
-//         .help_available(true)
-//         .add_command("add", Command(Arc::new(NoteAdd)))
-//         .add_command("del", Command(Arc::new(NoteRemove)))
-//         .add_command("list", Command(Arc::new(NoteList)))
-// }
+pub fn init_notes(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .prefix("note")
+        .guild_only(true)
+        .help_available(true)
+        .default_command(Command(Arc::new(NoteList)))
+        .add_command("add", Command(Arc::new(NoteAdd)))
+        .add_command("edit", Command(Arc::new(NoteEdit)))
+        .add_command("del", Command(Arc::new(NoteRemove)))
+        .add_command("list", Command(Arc::new(NoteList)))
+}
 
 pub fn init_roles(module: ModuleBuilder) -> ModuleBuilder {
     module
@@ -63,13 +121,23 @@ pub fn init_roles(module: ModuleBuilder) -> ModuleBuilder {
         .add_command("rc", Alias("rolecolor".to_string()))
 }
 
-// pub fn init_watchlist(module: ModuleBuilder) -> ModuleBuilder {
-//     module
-//         .prefixes(vec!["watchlist", "wl"])
-//         .guild_only(true)
-//         .help_available(true)
-//         .default_cmd(WatchlistList)
-//         .add_command("add", Command(Arc::new(WatchlistAdd)))
-//         .add_command("del", Command(Arc::new(WatchlistRemove)))
-//         .add_command("list", Command(Arc::new(WatchlistList)))
-// }
\ No newline at end of file
+pub fn init_warnings(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .guild_only(true)
+        .help_available(true)
+        .add_command("warn", Command(Arc::new(Warn)))
+        .add_command("warnings", Command(Arc::new(Warnings)))
+        .add_command("warns", Alias("warnings".to_string()))
+        .add_command("pardon", Command(Arc::new(Pardon)))
+}
+
+pub fn init_watchlist(module: ModuleBuilder) -> ModuleBuilder {
+    module
+        .prefixes(vec!["watchlist", "wl"])
+        .guild_only(true)
+        .help_available(true)
+        .default_command(Command(Arc::new(WatchlistList)))
+        .add_command("add", Command(Arc::new(WatchlistAdd)))
+        .add_command("del", Command(Arc::new(WatchlistRemove)))
+        .add_command("list", Command(Arc::new(WatchlistList)))
+}
\ No newline at end of file
@@ -1,106 +1,183 @@
-use crate::core::colours;
-use crate::core::consts::*;
-use crate::core::consts::DB as db;
-use crate::core::utils::*;
-use serenity::framework::standard::{
-    Args,
-    Command,
-    CommandError,
-    CommandOptions
-};
-use serenity::model::channel::Message;
-use serenity::model::Permissions;
-use serenity::prelude::Context;
+use chrono::Utc;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use std::error::Error;
 use std::sync::Arc;
 
+/// How many notes are shown per page of `note list`
+const NOTES_PER_PAGE: usize = 5;
+
 pub struct NoteAdd;
+#[async_trait]
 impl Command for NoteAdd {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("Add a note to a user.".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Add a note to a user. Notes are posted to the modlog unless the `note_add` log type is disabled.".to_string()),
             usage: Some("<user_resolvable> <note>".to_string()),
-            example: Some("@Adelyn test note".to_string()),
+            examples: vec!["@Adelyn test note".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
-                Some((user,_)) => {
-                    let note = args.rest().to_string();
-                    let data = db.new_note(user.0 as i64, guild_id.0 as i64, note, message.author.id.0 as i64)?;
-                    message.channel_id.say(format!("Added note `{}`.", data.note))?;
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user_id, member)) => {
+                    let note = args.rest().trim().to_string();
+                    if note.is_empty() {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide a note.")?.await?;
+                        return Ok(());
+                    }
+                    let data = ctx.db.new_note(user_id.0 as i64, guild_id.0 as i64, note, message.author.id.0 as i64)?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Added note `{}` (ID: {}).", data.note, data.id))?.await?;
+
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    let modlog_channel = log_channel(&guild_data, "note_add", guild_data.modlog_channel);
+                    if guild_data.modlog && modlog_channel.0 > 0 && !guild_data.logging.contains(&String::from("note_add")) {
+                        let embed = EmbedBuilder::new()
+                            .title("Note Added")
+                            .color(colors::BLUE)
+                            .description(format!("**User:** {}#{} ({})\n**Moderator:** {}#{} ({})\n**Note:** {}",
+                                member.user.name,
+                                member.user.discriminator,
+                                user_id.0,
+                                message.author.name,
+                                message.author.discriminator,
+                                message.author.id.0,
+                                data.note))
+                            .footer(EmbedFooterBuilder::new(format!("Note {}", data.id)))
+                            .timestamp(Utc::now().to_rfc3339())
+                            .build()?;
+                        ctx.http.create_message(modlog_channel).embed(embed)?.await?;
+                    }
                 },
-                None => { message.channel_id.say("I couldn't find that user")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct NoteEdit;
+#[async_trait]
+impl Command for NoteEdit {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Change the text of a note. Use `note list` to find the ID.".to_string()),
+            usage: Some("<user_resolvable> <id> <note>".to_string()),
+            examples: vec!["@Adelyn 3 updated note".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user_id, _)) => {
+                    let index = args.single::<i32>().unwrap_or(0);
+                    let note = args.rest().trim().to_string();
+                    if note.is_empty() {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("Please provide the new note.")?.await?;
+                        return Ok(());
+                    }
+                    match ctx.db.update_note(index, user_id.0 as i64, guild_id.0 as i64, note) {
+                        Ok(data) => { ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Note {} is now `{}`.", data.id, data.note))?.await?; },
+                        Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a note with that ID for that user.")?.await?; },
+                    }
+                },
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
+            }
+        }
+
         Ok(())
     }
 }
 
 pub struct NoteRemove;
+#[async_trait]
 impl Command for NoteRemove {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("Delete a note from a user.".to_string()),
-            usage: Some("<user_resolvable> <index>".to_string()),
-            example: Some("@Adelyn 3".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Delete a note from a user.".to_string()),
+            usage: Some("<user_resolvable> <id>".to_string()),
+            examples: vec!["@Adelyn 3".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
-                Some((user,_)) => {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user_id, _)) => {
                     let index = args.single::<i32>().unwrap_or(0);
-                    let data = db.del_note(index, user.0 as i64, guild_id.0 as i64)?;
-                    message.channel_id.say(format!("Deleted note `{}`.", data))?;
+                    match ctx.db.del_note(index, user_id.0 as i64, guild_id.0 as i64) {
+                        Ok(data) => { ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Deleted note `{}`.", data))?.await?; },
+                        Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a note with that ID for that user.")?.await?; },
+                    }
                 },
-                None => { message.channel_id.say("I couldn't find that user")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
         Ok(())
     }
 }
 
 pub struct NoteList;
+#[async_trait]
 impl Command for NoteList {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("List all notes for a user.".to_string()),
-            usage: Some("<user_resolvable>".to_string()),
-            example: Some("@Adelyn".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List notes for a user, newest first.".to_string()),
+            usage: Some("<user_resolvable> [page]".to_string()),
+            examples: vec!["@Adelyn".to_string(), "@Adelyn 2".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
                 Some((user_id, member)) => {
-                    let notes = db.get_notes(user_id.0 as i64, guild_id.0 as i64)?;
-                    let notes_fmt = notes.iter().map(|n| n.to_string()).collect::<Vec<String>>().join("\n\n");
-                    message.channel_id.send_message(|m| m
-                        .embed(|e| e
-                            .colour(*colours::MAIN)
-                            .title(format!("Notes for {}", member.display_name().into_owned()))
-                            .description(notes_fmt)
-                    ))?;
+                    let mut notes = ctx.db.get_notes(user_id.0 as i64, guild_id.0 as i64)?;
+                    notes.sort_by(|a, b| b.id.cmp(&a.id));
+                    let (notes, page, pages) = paginate(&notes, args.single::<usize>().unwrap_or(1), NOTES_PER_PAGE);
+                    let notes_fmt = if notes.is_empty() {
+                        String::from("No notes")
+                    } else {
+                        notes.iter().map(|n| n.to_string()).collect::<Vec<String>>().join("\n\n")
+                    };
+                    let embed = EmbedBuilder::new()
+                        .color(colors::MAIN)
+                        .title(format!("Notes for {}", member.nick.clone().unwrap_or(member.user.name.clone())))
+                        .description(notes_fmt)
+                        .footer(EmbedFooterBuilder::new(format!("Page {} of {}", page, pages)))
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
                 },
-                None => { message.channel_id.say("I couldn't find that user")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
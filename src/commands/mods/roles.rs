@@ -1,6 +1,7 @@
 use chrono::{Duration, Utc};
 use momiji::Context;
 use momiji::core::consts::*;
+use momiji::core::timers::TimerPayload;
 use momiji::core::utils::*;
 use momiji::framework::args::Args;
 use momiji::framework::command::{Command, Options};
@@ -69,16 +70,16 @@ impl Command for Register {
                                 Some(dur) => dur,
                                 None => DAY as i32,
                             };
-                            let data = format!("COOLDOWN||{}||{}||{}||{}",
-                                user_id.0,
-                                guild_id.0,
-                                member_role,
-                                role);
+                            let payload = TimerPayload::Cooldown {
+                                user_id,
+                                guild_id,
+                                member_role: RoleId(member_role as u64),
+                                cooldown_role: RoleId(role as u64),
+                            };
                             let start_time = Utc::now();
                             let end_time = start_time.timestamp() + dur as i64;
                             cooldown_end_time = start_time.checked_add_signed(Duration::seconds(dur as i64));
-                            db.new_timer(start_time.timestamp(), end_time, data)?;
-                            ctx.tc.request();
+                            ctx.tc.schedule(payload, start_time.timestamp(), end_time)?;
                         }
                     } else if let Some(role) = guild_data.register_member_role {
                         http.add_guild_member_role(guild_id, user_id, RoleId(role as u64)).await?;
@@ -0,0 +1,166 @@
+use chrono::Utc;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::moderation;
+use momiji::core::utils::*;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder};
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::UserId;
+use std::error::Error;
+use std::sync::Arc;
+
+/// How many warnings are shown per page of `warnings`
+const WARNS_PER_PAGE: usize = 10;
+
+pub struct Warn;
+#[async_trait]
+impl Command for Warn {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Warn a user. Warnings are recorded as cases and may trigger the escalations set with `config warns`.".to_string()),
+            usage: Some("<user_resolvable> [/r reason]".to_string()),
+            examples: vec!["@Adelyn /r spam".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            match resolve_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user, member)) => {
+                    if member.is_some() && !moderation::can_moderate(guild_id, message.author.id, user.id, &ctx) {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content("You can't warn someone at or above your highest role.")?.await?;
+                        return Ok(());
+                    }
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    let reason = get_switches(args.rest().to_string())
+                        .get("r")
+                        .cloned()
+                        .filter(|r| !r.is_empty());
+                    moderation::warn(&ctx, &guild_data, guild_id, &user, &message.author, reason, message.channel_id).await?;
+                    if guild_data.modlog && guild_data.modlog_channel > 0 {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Warned {}.", member.and_then(|m| m.nick.clone()).unwrap_or(user.name)))?.await?;
+                    }
+                },
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Warnings;
+#[async_trait]
+impl Command for Warnings {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List a user's warnings, newest first. Pardoned warnings are struck through.".to_string()),
+            usage: Some("<user_resolvable> [page]".to_string()),
+            examples: vec!["@Adelyn".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            match resolve_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
+                Some((user, member)) => {
+                    let warns = ctx.db.get_cases(user.id.0 as i64, guild_id.0 as i64)?
+                        .into_iter()
+                        .filter(|c| c.casetype == "Warn")
+                        .collect::<Vec<_>>();
+                    let active = warns.iter().filter(|c| !c.pardoned).count();
+                    let (warns, page, pages) = paginate(&warns, args.single::<usize>().unwrap_or(1), WARNS_PER_PAGE);
+                    let warns_fmt = if warns.is_empty() {
+                        String::from("No warnings")
+                    } else {
+                        warns.iter().map(|c| {
+                            let line = format!("`#{}` {}: {}",
+                                c.case_number,
+                                c.timestamp.format("%F"),
+                                if c.reason.is_empty() { "No reason given" } else { c.reason.as_str() });
+                            if c.pardoned { format!("~~{}~~", line) } else { line }
+                        }).collect::<Vec<String>>().join("\n")
+                    };
+                    let embed = EmbedBuilder::new()
+                        .color(colors::MAIN)
+                        .title(format!("Warnings for {} ({} active)", member.and_then(|m| m.nick.clone()).unwrap_or(user.name), active))
+                        .description(warns_fmt)
+                        .footer(EmbedFooterBuilder::new(format!("Page {} of {}", page, pages)))
+                        .build()?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+                },
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Pardon;
+#[async_trait]
+impl Command for Pardon {
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Pardon a warning so it no longer counts towards escalations. The case is kept.".to_string()),
+            usage: Some("<case number>".to_string()),
+            examples: vec!["12".to_string()],
+            required_permissions: Permissions::MANAGE_MESSAGES,
+            guild_only: true,
+            ..Options::default()
+        };
+        Arc::new(options)
+    }
+
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if let Some(guild_id) = message.guild_id {
+            let number = args.single::<i32>().unwrap_or(0);
+            match ctx.db.get_case(guild_id.0 as i64, number) {
+                Ok(case) if case.casetype != "Warn" => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("Only warnings can be pardoned.")?.await?;
+                },
+                Ok(case) if case.pardoned => {
+                    ctx.http.create_message(message.channel_id).reply(message.id).content("That warning is already pardoned.")?.await?;
+                },
+                Ok(case) => {
+                    ctx.db.pardon_case(guild_id.0 as i64, case.case_number)?;
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    let user = match ctx.http.user(UserId(case.user_id as u64)).await {
+                        Ok(Some(user)) => format!("{}#{} ({})", user.name, user.discriminator, case.user_id),
+                        _ => case.user_id.to_string(),
+                    };
+                    let embed = EmbedBuilder::new()
+                        .title("Warning Pardoned")
+                        .color(colors::GREEN)
+                        .description(format!("**User:** {}\n**Moderator:** {}#{} ({})\n**Warning:** {}",
+                            user,
+                            message.author.name,
+                            message.author.discriminator,
+                            message.author.id.0,
+                            if case.reason.is_empty() { "No reason given" } else { case.reason.as_str() }))
+                        .footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)))
+                        .timestamp(Utc::now().to_rfc3339())
+                        .build()?;
+                    moderation::send_modlog(&ctx, &guild_data, message.channel_id, embed).await?;
+                    if guild_data.modlog && guild_data.modlog_channel > 0 {
+                        ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Pardoned case {}.", case.case_number))?.await?;
+                    }
+                },
+                Err(_) => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find a case with that number.")?.await?; },
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,111 +1,123 @@
-use crate::core::colours;
-use crate::core::consts::*;
-use crate::core::consts::DB as db;
-use crate::core::utils::*;
-use serenity::framework::standard::{
-    Args,
-    Command,
-    CommandError,
-    CommandOptions
-};
-use serenity::model::channel::Message;
-use serenity::model::id::UserId;
-use serenity::model::Permissions;
-use serenity::prelude::Context;
+use momiji::Context;
+use momiji::core::consts::*;
+use momiji::core::utils::*;
+use momiji::db::models::UserUpdate;
+use momiji::framework::args::Args;
+use momiji::framework::command::{Command, Options};
+use twilight_embed_builder::EmbedBuilder;
+use twilight_model::channel::Message;
+use twilight_model::guild::Permissions;
+use twilight_model::id::UserId;
+use std::error::Error;
 use std::sync::Arc;
 
 pub struct WatchlistAdd;
+#[async_trait]
 impl Command for WatchlistAdd {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("Add a user to the watchlist.".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Add a user to the watchlist. Their activity is mirrored to the channel set with `config watchlist`.".to_string()),
             usage: Some("<user_resolvable>".to_string()),
-            example: Some("@Adelyn".to_string()),
+            examples: vec!["@Adelyn".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
                 Some((user_id, member)) => {
-                    let mut user_data = db.get_user(user_id.0 as i64, guild_id.0 as i64)?;
+                    let mut user_data = ctx.db.upsert_user(UserUpdate {
+                        id: user_id.0 as i64,
+                        guild_id: guild_id.0 as i64,
+                        username: member.user.name.clone(),
+                    })?;
                     user_data.watchlist = true;
-                    db.update_user(user_id.0 as i64, guild_id.0 as i64, user_data)?;
-                    message.channel_id.say(format!("Set {} to watchlist status.", member.display_name().into_owned()))?;
+                    ctx.db.update_user(user_id.0 as i64, guild_id.0 as i64, user_data)?;
+                    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+                    ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Set {} to watchlist status.{}",
+                        member.nick.clone().unwrap_or(member.user.name.clone()),
+                        if guild_data.watchlist_channel > 0 { "" } else { " Set a channel with `config watchlist channel` to see their activity." }
+                    ))?.await?;
                 },
-                None => { message.channel_id.say("I couldn't find that user")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
         Ok(())
     }
 }
 
 pub struct WatchlistRemove;
+#[async_trait]
 impl Command for WatchlistRemove {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("Remove a user from the watchlist.".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("Remove a user from the watchlist.".to_string()),
             usage: Some("<user_resolvable>".to_string()),
-            example: Some("@Adelyn".to_string()),
+            examples: vec!["@Adelyn".to_string()],
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, mut args: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id) {
+            match parse_user(args.single::<String>().unwrap_or(String::new()), guild_id, ctx.clone()).await {
                 Some((user_id, member)) => {
-                    let mut user_data = db.get_user(user_id.0 as i64, guild_id.0 as i64)?;
-                    user_data.watchlist = false;
-                    db.update_user(user_id.0 as i64, guild_id.0 as i64, user_data)?;
-                    message.channel_id.say(format!("Unset {} from watchlist status.", member.display_name().into_owned()))?;
+                    match ctx.db.get_user(user_id.0 as i64, guild_id.0 as i64) {
+                        Ok(mut user_data) if user_data.watchlist => {
+                            user_data.watchlist = false;
+                            ctx.db.update_user(user_id.0 as i64, guild_id.0 as i64, user_data)?;
+                            ctx.http.create_message(message.channel_id).reply(message.id).content(format!("Unset {} from watchlist status.", member.nick.clone().unwrap_or(member.user.name.clone())))?.await?;
+                        },
+                        _ => { ctx.http.create_message(message.channel_id).reply(message.id).content("That user isn't on the watchlist.")?.await?; },
+                    }
                 },
-                None => { message.channel_id.say("I couldn't find that user")?; }
+                None => { ctx.http.create_message(message.channel_id).reply(message.id).content("I couldn't find that user")?.await?; }
             }
-        } else { failed!(GUILDID_FAIL); }
+        }
+
         Ok(())
     }
 }
 
 pub struct WatchlistList;
+#[async_trait]
 impl Command for WatchlistList {
-    fn options(&self) -> Arc<CommandOptions> {
-        let default = CommandOptions::default();
-        let options = CommandOptions {
-            desc: Some("List users on the watchlist.".to_string()),
+    fn options(&self) -> Arc<Options> {
+        let options = Options {
+            description: Some("List users on the watchlist.".to_string()),
             required_permissions: Permissions::MANAGE_MESSAGES,
-            ..default
+            guild_only: true,
+            ..Options::default()
         };
         Arc::new(options)
     }
 
-    async fn run(&self, message: Message, args: Args, http: HttpClient, cache: InMemoryCache, db: DatabaseConnection, _: TimerClient) -> Result<(), Box<dyn Error + Send + Sync>> {
+    async fn run(&self, message: Message, _: Args, ctx: Context) -> Result<(), Box<dyn Error + Send + Sync>> {
         if let Some(guild_id) = message.guild_id {
-            let users = db.get_users(guild_id.0 as i64)?;
+            let users = ctx.db.get_users(guild_id.0 as i64)?;
             let user_map = users.iter()
                 .filter(|e| e.watchlist)
-                .map(|u| {
-                    match UserId(u.id as u64).to_user() {
-                        Ok(user) => user.tag(),
-                        Err(_) => format!("<#{}>", u.id),
-                    }
+                .map(|u| match ctx.cache.user(UserId(u.id as u64)) {
+                    Some(user) => format!("{}#{} ({})", user.name, user.discriminator, u.id),
+                    None => format!("<@{}> ({})", u.id, u.id),
                 })
-                .collect::<Vec<String>>()
-                .join("\n");
-            message.channel_id.send_message(|m| m
-                .embed(|e| e
-                    .title("Watchlist")
-                    .description(user_map)
-                    .colour(*colours::MAIN)
-            ))?;
-        } else { failed!(GUILDID_FAIL); }
+                .collect::<Vec<String>>();
+            let embed = EmbedBuilder::new()
+                .title("Watchlist")
+                .description(if user_map.is_empty() { "No users".to_string() } else { user_map.join("\n") })
+                .color(colors::MAIN)
+                .build()?;
+            ctx.http.create_message(message.channel_id).reply(message.id).embed(embed)?.await?;
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,173 @@
+//! Raid protection. Joins are counted per guild and when too many arrive at once the guild is locked
+//! down as configured with `config raid`, until `antiraid off`.
+use crate::Context;
+use crate::core::consts::*;
+use crate::core::lockdown;
+use crate::core::moderation;
+use crate::core::utils::*;
+use crate::db::models::Guild;
+use chrono::Utc;
+use std::error::Error;
+use tracing::{event, Level};
+use twilight_embed_builder::EmbedBuilder;
+use twilight_http::request::AuditLogReason;
+use twilight_model::guild::{Member, VerificationLevel};
+use twilight_model::id::{ChannelId, GuildId, UserId};
+
+/// What raid protection does to the guild when it triggers
+pub const RAID_ACTIONS: [&str; 2] = ["verification", "lock"];
+/// What raid protection does to the members who joined in the raid
+pub const RAID_PUNISHMENTS: [&str; 3] = ["none", "kick", "ban"];
+
+/// Counts a join towards raid detection. Accounts younger than `raid_account_age` count twice.
+/// While a raid is active, every new member is punished as configured.
+pub async fn check_join(member: &Member, guild_data: &Guild, ctx: &Context) -> Result<(), Box<dyn Error + Send + Sync>> {
+    if guild_data.raid_joins <= 0 || member.user.bot { return Ok(()); }
+    let guild_id = member.guild_id;
+    if guild_data.raid_active {
+        punish(ctx, guild_data, guild_id, &[member.user.id]).await;
+        return Ok(());
+    }
+
+    let now = Utc::now().timestamp();
+    let age = now - snowflake_time(member.user.id.0).timestamp();
+    let weight = if guild_data.raid_account_age > 0 && age < guild_data.raid_account_age { 2 } else { 1 };
+    let joins = ctx.automod.record_join(guild_id, member.user.id, weight, now, guild_data.raid_seconds as i64);
+    if joins.iter().map(|(_, _, w)| w).sum::<i32>() >= guild_data.raid_joins {
+        ctx.automod.forget_joins(guild_id);
+        let burst = joins.iter().map(|(_, user_id, _)| *user_id).collect::<Vec<UserId>>();
+        start(ctx, guild_id, &burst).await?;
+    }
+
+    Ok(())
+}
+
+/// Locks the guild down as configured, punishes `burst` and alerts the modlog
+pub async fn start(ctx: &Context, guild_id: GuildId, burst: &[UserId]) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if guild_data.raid_active { return Ok(()); }
+    guild_data.raid_active = true;
+
+    let measure = match guild_data.raid_action.as_str() {
+        "lock" => {
+            let channels = if guild_data.raid_channels.is_empty() {
+                lockdown::text_channels(guild_id, ctx)
+            } else {
+                guild_data.raid_channels.iter().map(|id| ChannelId(*id as u64)).collect()
+            };
+            let mut locked = 0;
+            for channel_id in channels {
+                match lockdown::lock_channel(ctx, guild_id, channel_id, true).await {
+                    Ok(true) => { locked += 1; },
+                    Ok(false) => {},
+                    Err(why) => { event!(Level::DEBUG, "Unable to lock {} during raid in {}: {}", channel_id, guild_id, why); },
+                }
+            }
+            format!("Locked {} channels", locked)
+        },
+        _ => {
+            let previous = ctx.cache.guild(guild_id)
+                .map(|g| g.verification_level)
+                .unwrap_or(VerificationLevel::None);
+            guild_data.raid_verification_level = Some(previous as i16);
+            ctx.http.update_guild(guild_id)
+                .verification_level(VerificationLevel::VeryHigh)
+                .reason("Raid protection")?
+                .await?;
+            format!("Raised verification level from {:?} to VeryHigh", previous)
+        },
+    };
+    let guild_data = ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+    punish(ctx, &guild_data, guild_id, burst).await;
+
+    if guild_data.modlog && guild_data.modlog_channel > 0 {
+        let embed = EmbedBuilder::new()
+            .title("Raid Detected")
+            .color(colors::RED)
+            .description(format!("**Joins:** {} in the last {}s\n**Action:** {}\n**Punishment:** {}\n**Members:**\n{}\nUse `antiraid off` to restore the server once the raid is over.",
+                burst.len(),
+                guild_data.raid_seconds,
+                measure,
+                guild_data.raid_punish,
+                burst.iter().map(|id| format!("<@{}> ({})", id.0, id.0)).collect::<Vec<String>>().join("\n")))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+        moderation::send_modlog(ctx, &guild_data, ChannelId(guild_data.modlog_channel as u64), embed).await?;
+    }
+
+    Ok(())
+}
+
+/// Lifts raid protection, putting back the previous verification level and unlocking channels it locked
+pub async fn stop(ctx: &Context, guild_id: GuildId) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let mut guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if !guild_data.raid_active { return Ok(false); }
+    ctx.automod.forget_joins(guild_id);
+
+    let mut restored = Vec::new();
+    if let Some(level) = guild_data.raid_verification_level.take() {
+        let level = match level {
+            0 => VerificationLevel::None,
+            1 => VerificationLevel::Low,
+            2 => VerificationLevel::Medium,
+            3 => VerificationLevel::High,
+            _ => VerificationLevel::VeryHigh,
+        };
+        ctx.http.update_guild(guild_id)
+            .verification_level(level)
+            .reason("Raid protection lifted")?
+            .await?;
+        restored.push(format!("Verification level set back to {:?}", level));
+    }
+    let mut unlocked = 0;
+    for lock in ctx.db.get_channel_locks(guild_id.0 as i64)?.into_iter().filter(|l| l.raid) {
+        match lockdown::unlock_channel(ctx, guild_id, ChannelId(lock.channel_id as u64)).await {
+            Ok(true) => { unlocked += 1; },
+            Ok(false) => {},
+            Err(why) => { event!(Level::DEBUG, "Unable to unlock {} after raid in {}: {}", lock.channel_id, guild_id, why); },
+        }
+    }
+    if unlocked > 0 { restored.push(format!("Unlocked {} channels", unlocked)); }
+    guild_data.raid_active = false;
+    ctx.db.update_guild(guild_id.0 as i64, guild_data)?;
+    let guild_data = ctx.db.clear_raid_verification_level(guild_id.0 as i64)?;
+
+    if guild_data.modlog && guild_data.modlog_channel > 0 {
+        let embed = EmbedBuilder::new()
+            .title("Raid Protection Lifted")
+            .color(colors::GREEN)
+            .description(if restored.is_empty() { String::from("Nothing to restore") } else { restored.join("\n") })
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+        moderation::send_modlog(ctx, &guild_data, ChannelId(guild_data.modlog_channel as u64), embed).await?;
+    }
+
+    Ok(true)
+}
+
+/// Kicks or bans raiders as configured, recording a case for each. These are summarised in the raid alert
+/// rather than posted one by one.
+async fn punish(ctx: &Context, guild_data: &Guild, guild_id: GuildId, users: &[UserId]) {
+    let reason = String::from("Raid protection");
+    for user_id in users {
+        let (casetype, result) = match guild_data.raid_punish.as_str() {
+            "kick" => ("Kick", match ctx.http.remove_guild_member(guild_id, *user_id).reason(reason.clone()) {
+                Ok(request) => request.await.map(|_| ()).map_err(|e| e.to_string()),
+                Err(why) => Err(why.to_string()),
+            }),
+            "ban" => ("Ban", match ctx.http.create_ban(guild_id, *user_id).reason(reason.clone()) {
+                Ok(request) => request.await.map(|_| ()).map_err(|e| e.to_string()),
+                Err(why) => Err(why.to_string()),
+            }),
+            _ => return,
+        };
+        match result {
+            Ok(_) => {
+                if let Err(why) = ctx.db.new_case(user_id.0 as i64, guild_id.0 as i64, casetype.to_string(), Some(reason.clone()), ctx.user.id.0 as i64) {
+                    event!(Level::DEBUG, "Unable to record raid case for {} in {}: {}", user_id, guild_id, why);
+                }
+            },
+            Err(why) => { event!(Level::DEBUG, "Unable to punish raider {} in {}: {}", user_id, guild_id, why); },
+        }
+    }
+}
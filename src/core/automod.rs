@@ -0,0 +1,372 @@
+//! Automatic moderation of messages. Rules are configured per guild with `config spam`, `config filter` and `config links`.
+use crate::Context;
+use crate::core::consts::*;
+use crate::core::moderation;
+use crate::core::utils::*;
+use crate::db::DatabaseConnection;
+use crate::db::models::{Filter, Guild};
+use diesel::result::QueryResult;
+use chrono::Utc;
+use parking_lot::Mutex;
+use regex::{Regex, RegexBuilder};
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::sync::Arc;
+use tracing::{event, Level};
+use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder, ImageSource};
+use twilight_model::channel::Message;
+use twilight_model::id::{ChannelId, GuildId, MessageId, UserId};
+use twilight_model::user::User;
+
+lazy_static::lazy_static! {
+    static ref INVITE: Regex = Regex::new(r"(?i)(?:discord(?:app)?\.com/invite|discord\.(?:gg|io|me|li))/([a-z0-9-]+)").expect("Failed to create Regex");
+    static ref URL_HOST: Regex = Regex::new(r"(?i)\bhttps?://(?:[^\s/@]*@)?([a-z0-9.-]+)").expect("Failed to create Regex");
+}
+
+/// The actions an automod rule can take
+pub const AUTOMOD_ACTIONS: [&str; 4] = ["delete", "warn", "mute", "kick"];
+/// How long messages are remembered for flood and duplicate detection, in seconds
+const HISTORY_SECONDS: i64 = 5 * MIN as i64;
+/// The most messages remembered per member
+const HISTORY_SIZE: usize = 50;
+
+/// A message remembered for spam detection
+#[derive(Clone, Debug)]
+struct SeenMessage {
+    /// When the message was seen, in milliseconds
+    time: i64,
+    channel_id: ChannelId,
+    id: MessageId,
+    content: String,
+}
+
+/// Recent activity used by the automod and raid protection, shared through `Context`
+#[derive(Clone, Default)]
+pub struct AutomodState {
+    messages: Arc<Mutex<HashMap<(GuildId, UserId), VecDeque<SeenMessage>>>>,
+    joins: Arc<Mutex<HashMap<GuildId, VecDeque<(i64, UserId, i32)>>>>,
+    filters: Arc<Mutex<HashMap<GuildId, Arc<Vec<(Filter, Regex)>>>>>,
+}
+
+impl AutomodState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remembers a message and returns the member's messages from the last `HISTORY_SECONDS`, oldest first.
+    /// `now` is in milliseconds.
+    fn record(&self, guild_id: GuildId, message: &Message, now: i64) -> Vec<SeenMessage> {
+        let mut messages = self.messages.lock();
+        let history = messages.entry((guild_id, message.author.id)).or_insert_with(VecDeque::new);
+        history.push_back(SeenMessage {
+            time: now,
+            channel_id: message.channel_id,
+            id: message.id,
+            content: message.content.trim().to_lowercase(),
+        });
+        while history.len() > HISTORY_SIZE || history.front().map(|m| now - m.time > HISTORY_SECONDS * 1000).unwrap_or(false) {
+            history.pop_front();
+        }
+        history.iter().cloned().collect()
+    }
+
+    /// Forgets a member's messages, so one burst of spam is only acted on once
+    fn forget(&self, guild_id: GuildId, user_id: UserId) {
+        self.messages.lock().remove(&(guild_id, user_id));
+    }
+
+    /// Remembers a join with the given weight and returns the joins from the last `seconds` seconds, oldest first
+    pub(crate) fn record_join(&self, guild_id: GuildId, user_id: UserId, weight: i32, now: i64, seconds: i64) -> Vec<(i64, UserId, i32)> {
+        let mut joins = self.joins.lock();
+        let history = joins.entry(guild_id).or_insert_with(VecDeque::new);
+        history.push_back((now, user_id, weight));
+        while history.front().map(|(time, _, _)| now - time > seconds).unwrap_or(false) {
+            history.pop_front();
+        }
+        history.iter().cloned().collect()
+    }
+
+    /// Forgets a guild's joins, once a raid has been handled or lifted
+    pub(crate) fn forget_joins(&self, guild_id: GuildId) {
+        self.joins.lock().remove(&guild_id);
+    }
+
+    /// The guild's compiled filters. They are loaded from the database once and kept until `forget_filters`.
+    /// Filters which don't compile are skipped.
+    fn filters(&self, guild_id: GuildId, db: &DatabaseConnection) -> QueryResult<Arc<Vec<(Filter, Regex)>>> {
+        if let Some(filters) = self.filters.lock().get(&guild_id) {
+            return Ok(filters.clone());
+        }
+        let filters = Arc::new(db.get_filters(guild_id.0 as i64)?
+            .into_iter()
+            .filter_map(|filter| match filter_regex(&filter) {
+                Ok(re) => Some((filter, re)),
+                Err(why) => {
+                    event!(Level::DEBUG, "Invalid filter {} in {}: {}", filter.id, guild_id, why);
+                    None
+                },
+            })
+            .collect::<Vec<_>>());
+        self.filters.lock().insert(guild_id, filters.clone());
+        Ok(filters)
+    }
+
+    /// Forgets a guild's compiled filters, so changes made with `config filter` take effect
+    pub fn forget_filters(&self, guild_id: GuildId) {
+        self.filters.lock().remove(&guild_id);
+    }
+}
+
+/// A rule which a message broke
+struct Violation {
+    rule: &'static str,
+    action: String,
+    detail: String,
+    messages: Vec<(ChannelId, MessageId)>,
+}
+
+impl Violation {
+    fn reason(&self) -> String {
+        format!("Automod {}: {}", self.rule, self.detail)
+    }
+}
+
+/// Whether the automod should leave a member's message alone. Bots, ignored channels and members with
+/// a mod or admin role are exempt.
+pub fn is_exempt(guild_id: GuildId, channel_id: ChannelId, author: &User, guild_data: &Guild, ctx: &Context) -> bool {
+    if author.bot { return true; }
+    if guild_data.ignored_channels.contains(&(channel_id.0 as i64)) { return true; }
+    match ctx.cache.member(guild_id, author.id) {
+        Some(member) => check_rank(guild_data.mod_roles.clone(), &member.roles) || check_rank(guild_data.admin_roles.clone(), &member.roles),
+        None => false,
+    }
+}
+
+/// A created or edited message, as seen by the content filters
+#[derive(Clone, Copy)]
+pub struct FilterTarget<'a> {
+    pub guild_id: GuildId,
+    pub channel_id: ChannelId,
+    pub message_id: MessageId,
+    pub author: &'a User,
+    pub content: &'a str,
+}
+
+/// Compiles a filter. Words match case-insensitively on word boundaries, patterns are used as given
+/// but also match case-insensitively.
+pub fn filter_regex(filter: &Filter) -> Result<Regex, regex::Error> {
+    let pattern = if filter.regex {
+        filter.pattern.clone()
+    } else {
+        format!(r"\b{}\b", regex::escape(filter.pattern.as_str()))
+    };
+    RegexBuilder::new(pattern.as_str())
+        .case_insensitive(true)
+        .build()
+}
+
+/// Checks a message against the guild's word and regex filters. A matching message is deleted, the match is
+/// recorded in the audit channel and the author is warned if `filter_warn` is set.
+/// Returns whether the message was acted on.
+pub async fn check_filter(target: FilterTarget<'_>, guild_data: &Guild, ctx: &Context) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    if is_exempt(target.guild_id, target.channel_id, target.author, guild_data, ctx) { return Ok(false); }
+    let filters = ctx.automod.filters(target.guild_id, &ctx.db)?;
+    let matched = filters.iter()
+        .find_map(|(filter, re)| re.find(target.content).map(|m| (filter, m.as_str().to_string())));
+    let (filter, text) = match matched {
+        Some(matched) => matched,
+        None => return Ok(false),
+    };
+
+    if let Err(why) = ctx.http.delete_message(target.channel_id, target.message_id).await {
+        event!(Level::DEBUG, "Unable to delete message {} in {}: {}", target.message_id, target.channel_id, why);
+    }
+
+    let audit_channel = log_channel(guild_data, "message_filter", guild_data.audit_channel);
+    if guild_data.audit && audit_channel.0 > 0 && !guild_data.logging.contains(&String::from("message_filter")) {
+        let embed = EmbedBuilder::new()
+            .title("Message Filtered")
+            .color(colors::RED)
+            .footer(EmbedFooterBuilder::new(format!("ID: {}", target.message_id.0)))
+            .description(format!("**Author:** {}#{} ({}) - <@{}>\n**Channel:** <#{}>\n**Filter:** {}\n**Matched:** {}\n**Content:**\n{}",
+                target.author.name,
+                target.author.discriminator,
+                target.author.id.0,
+                target.author.id.0,
+                target.channel_id.0,
+                filter,
+                text,
+                target.content))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+        ctx.http.create_message(audit_channel).embed(embed)?.await?;
+    }
+
+    if guild_data.filter_warn {
+        let moderator = moderation::bot_user(ctx).await?;
+        let reason = Some(format!("Automod filter: matched {}", text));
+        moderation::warn(ctx, guild_data, target.guild_id, target.author, &moderator, reason, target.channel_id).await?;
+    }
+
+    Ok(true)
+}
+
+/// Whether a host is a domain or one of its subdomains
+fn domain_matches(host: &str, domain: &str) -> bool {
+    let domain = domain.trim_start_matches("*.").trim_start_matches('.');
+    host == domain || host.ends_with(format!(".{}", domain).as_str())
+}
+
+/// Checks a message for Discord invites and links to blocked domains. Invites to the guild itself may be
+/// allowed, and domains on the allowlist are never blocked. Offending messages are handled like spam,
+/// using `link_filter_action`.
+/// Returns whether the message was acted on.
+pub async fn check_links(target: FilterTarget<'_>, guild_data: &Guild, ctx: &Context) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    if !guild_data.invite_filter && guild_data.blocked_domains.is_empty() { return Ok(false); }
+    if is_exempt(target.guild_id, target.channel_id, target.author, guild_data, ctx) { return Ok(false); }
+
+    let mut detail = None;
+    if guild_data.invite_filter {
+        for code in INVITE.captures_iter(target.content).map(|c| c[1].to_string()) {
+            if guild_data.invite_allow_own {
+                if let Ok(Some(invite)) = ctx.http.invite(code.as_str()).await {
+                    if invite.guild.map(|g| g.id) == Some(target.guild_id) { continue; }
+                }
+            }
+            detail = Some(format!("posted invite {}", code));
+            break;
+        }
+    }
+    if detail.is_none() {
+        detail = URL_HOST.captures_iter(target.content)
+            .map(|c| c[1].trim_end_matches('.').to_lowercase())
+            .filter(|host| !guild_data.allowed_domains.iter().any(|d| domain_matches(host, d)))
+            .find(|host| guild_data.blocked_domains.iter().any(|d| domain_matches(host, d)))
+            .map(|host| format!("linked blocked domain {}", host));
+    }
+
+    match detail {
+        Some(detail) => {
+            let violation = Violation {
+                rule: "link filter",
+                action: guild_data.link_filter_action.clone(),
+                detail,
+                messages: vec![(target.channel_id, target.message_id)],
+            };
+            act(ctx, guild_data, target.guild_id, target.author, &violation).await?;
+            Ok(true)
+        },
+        None => Ok(false),
+    }
+}
+
+/// Checks a new message for floods, duplicates and mass mentions, and acts on the first rule it breaks.
+/// Returns whether the message was acted on.
+pub async fn check_spam(message: &Message, guild_data: &Guild, ctx: &Context) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let guild_id = match message.guild_id {
+        Some(guild_id) => guild_id,
+        None => return Ok(false),
+    };
+    if message.webhook_id.is_some() || is_exempt(guild_id, message.channel_id, &message.author, guild_data, ctx) { return Ok(false); }
+
+    let now = Utc::now().timestamp_millis();
+    let history = ctx.automod.record(guild_id, message, now);
+    let this = vec![(message.channel_id, message.id)];
+
+    let mentions = message.mentions.len() + message.mention_roles.len();
+    let violation = if guild_data.spam_mention_count > 0 && mentions >= guild_data.spam_mention_count as usize {
+        Some(Violation {
+            rule: "mass mentions",
+            action: guild_data.spam_mention_action.clone(),
+            detail: format!("{} mentions in one message", mentions),
+            messages: this,
+        })
+    } else {
+        let flood = within(&history, now, guild_data.spam_flood_seconds, |_| true);
+        let content = message.content.trim().to_lowercase();
+        let duplicates = within(&history, now, guild_data.spam_duplicate_seconds, |m| !content.is_empty() && m.content == content);
+        if guild_data.spam_flood_count > 0 && flood.len() >= guild_data.spam_flood_count as usize {
+            Some(Violation {
+                rule: "flood",
+                action: guild_data.spam_flood_action.clone(),
+                detail: format!("{} messages in {}s", flood.len(), guild_data.spam_flood_seconds),
+                messages: flood,
+            })
+        } else if guild_data.spam_duplicate_count > 0 && duplicates.len() >= guild_data.spam_duplicate_count as usize {
+            Some(Violation {
+                rule: "duplicates",
+                action: guild_data.spam_duplicate_action.clone(),
+                detail: format!("{} identical messages in {}s", duplicates.len(), guild_data.spam_duplicate_seconds),
+                messages: duplicates,
+            })
+        } else {
+            None
+        }
+    };
+
+    match violation {
+        Some(violation) => {
+            ctx.automod.forget(guild_id, message.author.id);
+            act(ctx, guild_data, guild_id, &message.author, &violation).await?;
+            Ok(true)
+        },
+        None => Ok(false),
+    }
+}
+
+/// The remembered messages from the last `seconds` seconds which match `filter`
+fn within<F: Fn(&SeenMessage) -> bool>(history: &[SeenMessage], now: i64, seconds: i32, filter: F) -> Vec<(ChannelId, MessageId)> {
+    history.iter()
+        .filter(|m| now - m.time <= seconds as i64 * 1000)
+        .filter(|m| filter(m))
+        .map(|m| (m.channel_id, m.id))
+        .collect()
+}
+
+/// Deletes the offending messages, then applies the rule's action through the usual moderation paths
+async fn act(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, violation: &Violation) -> Result<(), Box<dyn Error + Send + Sync>> {
+    for (channel_id, message_id) in violation.messages.iter() {
+        if let Err(why) = ctx.http.delete_message(*channel_id, *message_id).await {
+            event!(Level::DEBUG, "Unable to delete message {} in {}: {}", message_id, channel_id, why);
+        }
+    }
+
+    let moderator = moderation::bot_user(ctx).await?;
+    let reason = Some(violation.reason());
+    let fallback = violation.messages.first().map(|(c, _)| *c).unwrap_or(ChannelId(0));
+    match violation.action.as_str() {
+        "warn" => { moderation::warn(ctx, guild_data, guild_id, user, &moderator, reason, fallback).await?; },
+        "mute" => match moderation::find_mute_role(guild_id, ctx) {
+            Some(mute_role) => { moderation::mute(ctx, guild_data, guild_id, &mute_role, user, &moderator, Some(guild_data.spam_mute_duration), reason, fallback).await?; },
+            None => {
+                event!(Level::DEBUG, "Automod in {} is set to mute but there is no mute role", guild_id);
+                log_delete(ctx, guild_data, guild_id, user, &moderator, violation, fallback).await?;
+            },
+        },
+        "kick" => { moderation::kick(ctx, guild_data, guild_id, user, &moderator, reason, fallback).await?; },
+        _ => { log_delete(ctx, guild_data, guild_id, user, &moderator, violation, fallback).await?; },
+    }
+
+    Ok(())
+}
+
+/// Records a case for messages the automod only deleted
+async fn log_delete(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, moderator: &User, violation: &Violation, fallback: ChannelId) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let case = ctx.db.new_case(user.id.0 as i64, guild_id.0 as i64, "Automod".to_string(), Some(violation.reason()), moderator.id.0 as i64)?;
+    let embed = EmbedBuilder::new()
+        .title("Messages Removed")
+        .color(colors::YELLOW)
+        .thumbnail(ImageSource::url(user_avatar_url(user))?)
+        .description(format!("**User:** {}#{} ({})\n**Messages:** {}\n**Reason:** {}",
+            user.name,
+            user.discriminator,
+            user.id.0,
+            violation.messages.len(),
+            violation.reason()))
+        .footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)))
+        .timestamp(Utc::now().to_rfc3339())
+        .build()?;
+    moderation::log_case(ctx, guild_data, fallback, case, embed).await?;
+
+    Ok(())
+}
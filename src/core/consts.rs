@@ -2,6 +2,10 @@ use twilight_model::id::{GuildId, ChannelId};
 
 lazy_static::lazy_static!{
     pub static ref LOG_TYPES: Vec<&'static str> = vec![
+        "channel_create",
+        "channel_delete",
+        "channel_update",
+        "emoji_change",
         "member_ban",
         "member_join",
         "member_kick",
@@ -9,9 +13,19 @@ lazy_static::lazy_static!{
         "member_unban",
         "message_delete",
         "message_edit",
+        "message_filter",
         "nickname_change",
+        "note_add",
         "role_change",
-        "username_change"];
+        "role_create",
+        "role_delete",
+        "role_update",
+        "username_change",
+        "voice_deafen",
+        "voice_join",
+        "voice_leave",
+        "voice_move",
+        "voice_mute"];
 }
 
 pub const WEEK: usize = 60*60*24*7;
@@ -37,4 +51,5 @@ pub mod colors {
     pub const BLUE: u32 = 0x6969ff;
     pub const RED: u32 = 0xff4040;
     pub const GREEN: u32 = 0x00ff7f;
+    pub const YELLOW: u32 = 0xffcc4d;
 }
\ No newline at end of file
@@ -1,6 +1,9 @@
 use chrono::Utc;
 use crate::Context;
+use crate::core::antiraid;
+use crate::core::automod;
 use crate::core::consts::*;
+use crate::core::lockdown;
 use crate::core::utils::*;
 use crate::framework::Framework;
 use futures::stream::StreamExt;
@@ -9,14 +12,35 @@ use tracing::{event, Level};
 use twilight_mention::Mention;
 use std::error::Error;
 use std::sync::Arc;
-use twilight_cache_inmemory::model::{CachedMember, CachedMessage};
+use twilight_cache_inmemory::model::{CachedEmoji, CachedMember, CachedMessage};
 use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder, ImageSource};
 use twilight_gateway::Event;
-use twilight_model::id::{ChannelId, GuildId, RoleId, UserId};
+use twilight_http::request::AuditLogReason;
+use twilight_model::channel::{Channel, GuildChannel};
+use twilight_model::channel::permission_overwrite::PermissionOverwriteType;
+use twilight_model::gateway::payload::{ChannelCreate, ChannelDelete, ChannelUpdate, MessageDeleteBulk};
+use twilight_model::guild::{Permissions, Role};
+use twilight_model::guild::audit_log::{AuditLogEntry, AuditLogEvent};
+use twilight_model::id::{ChannelId, EmojiId, GuildId, RoleId, UserId};
+use twilight_model::voice::VoiceState;
 
 use super::utils::build_welcome_embed;
 use super::utils::parse_welcome_items;
 
+/// How many recent audit log entries are searched when attributing an event
+const AUDIT_ENTRIES: u64 = 5;
+/// How old an audit log entry may be, in seconds, to still be attributed to an event
+const AUDIT_WINDOW: i64 = 15;
+
+/// Cached state from before an event was applied to the cache, used to log what changed
+enum Previous {
+    Channel(Arc<GuildChannel>),
+    Role(Arc<Role>),
+    Emojis(Vec<Arc<CachedEmoji>>),
+    Voice(Arc<VoiceState>),
+    Messages(Vec<Arc<CachedMessage>>),
+}
+
 pub struct EventHandler {
     framework: Arc<Framework>,
     ctx: Context,
@@ -35,6 +59,7 @@ impl EventHandler {
         while let Some((shard_id, event)) = events.next().await {
             let mut old_message = None;
             let mut old_member = None;
+            let mut previous = None;
             match &event {
                 Event::MessageDelete(message) => {
                     if let Some(message) = self.ctx.cache.message(message.channel_id, message.id) {
@@ -51,12 +76,36 @@ impl EventHandler {
                         old_member = Some(member);
                     }
                 },
+                Event::ChannelUpdate(ChannelUpdate(Channel::Guild(channel)))
+                | Event::ChannelDelete(ChannelDelete(Channel::Guild(channel))) => {
+                    previous = self.ctx.cache.guild_channel(channel.id()).map(Previous::Channel);
+                },
+                Event::RoleUpdate(update) => {
+                    previous = self.ctx.cache.role(update.role.id).map(Previous::Role);
+                },
+                Event::RoleDelete(delete) => {
+                    previous = self.ctx.cache.role(delete.role_id).map(Previous::Role);
+                },
+                Event::GuildEmojisUpdate(update) => {
+                    previous = self.ctx.cache.guild_emojis(update.guild_id)
+                        .map(|ids| Previous::Emojis(ids.iter().filter_map(|id| self.ctx.cache.emoji(*id)).collect()));
+                },
+                Event::MessageDeleteBulk(delete) => {
+                    previous = Some(Previous::Messages(delete.ids.iter()
+                        .filter_map(|id| self.ctx.cache.message(delete.channel_id, *id))
+                        .collect()));
+                },
+                Event::VoiceStateUpdate(update) => {
+                    if let Some(guild_id) = update.0.guild_id {
+                        previous = self.ctx.cache.voice_state(update.0.user_id, guild_id).map(Previous::Voice);
+                    }
+                },
                 _ => {}
             }
 
             self.ctx.cache.update(&event);
     
-            tokio::spawn(handle_event(shard_id, event, self.ctx.clone(), self.framework.clone(), old_message, old_member));
+            tokio::spawn(handle_event(shard_id, event, self.ctx.clone(), self.framework.clone(), old_message, old_member, previous));
         }
     }
 }
@@ -68,13 +117,43 @@ async fn handle_event(
     framework: Arc<Framework>,
     old_message: Option<Arc<CachedMessage>>,
     old_member: Option<Arc<CachedMember>>,
+    previous: Option<Previous>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
     let (cache, db, http) = {
         let c = ctx.clone();
         (c.cache, c.db, c.http)
     };
+    if let Err(why) = mirror_watchlist(&event, &ctx, &old_message, &old_member).await {
+        event!(Level::DEBUG, "Failed to mirror watchlist event: {}", why);
+    }
     match event {
         Event::MessageCreate(message) => {
+            if let Some(guild_id) = message.guild_id {
+                if let Ok(guild_data) = db.get_guild(guild_id.0 as i64) {
+                    let target = automod::FilterTarget {
+                        guild_id,
+                        channel_id: message.channel_id,
+                        message_id: message.id,
+                        author: &message.author,
+                        content: message.content.as_str(),
+                    };
+                    match automod::check_filter(target, &guild_data, &ctx).await {
+                        Ok(true) => return Ok(()),
+                        Ok(false) => {},
+                        Err(why) => { event!(Level::DEBUG, "Filter failed in {}: {}", guild_id, why); },
+                    }
+                    match automod::check_links(target, &guild_data, &ctx).await {
+                        Ok(true) => return Ok(()),
+                        Ok(false) => {},
+                        Err(why) => { event!(Level::DEBUG, "Link filter failed in {}: {}", guild_id, why); },
+                    }
+                    match automod::check_spam(&message.0, &guild_data, &ctx).await {
+                        Ok(true) => return Ok(()),
+                        Ok(false) => {},
+                        Err(why) => { event!(Level::DEBUG, "Automod failed in {}: {}", guild_id, why); },
+                    }
+                }
+            }
             if let Err(e) = (*framework).handle_command(message.0, ctx.clone()).await {
                 event!(Level::DEBUG, "{:?}", e);
             }
@@ -84,7 +163,7 @@ async fn handle_event(
                 let channel_name = cache.guild_channel(message.channel_id).and_then(|c| Some(c.name().to_string())).unwrap_or("unknown".to_string());
                 let guild_data = db.get_guild(guild_id.0 as i64)?;
                 if guild_data.logging.contains(&String::from("message_delete")) { return Ok(()); }
-                let audit_channel = ChannelId(guild_data.audit_channel as u64);
+                let audit_channel = log_channel(&guild_data, "message_delete", guild_data.audit_channel);
                 if guild_data.audit && audit_channel.0 > 0 {
                     if let Some(cached_message) = old_message {
                         if let Some(author) = cache.user(cached_message.author) {
@@ -127,6 +206,26 @@ async fn handle_event(
         Event::MessageUpdate(message) => {
             if message.author.clone().map(|u| u.bot).unwrap_or(false) { return Ok(()) }
             if let None = message.edited_timestamp { return Ok(()) }
+            if let (Some(guild_id), Some(content)) = (message.guild_id, &message.content) {
+                let author = message.author.clone()
+                    .or_else(|| old_message.as_ref().and_then(|old| cache.user(old.author)).map(|u| (*u).clone()));
+                if let (Some(author), Ok(guild_data)) = (author, db.get_guild(guild_id.0 as i64)) {
+                    let target = automod::FilterTarget {
+                        guild_id,
+                        channel_id: message.channel_id,
+                        message_id: message.id,
+                        author: &author,
+                        content: content.as_str(),
+                    };
+                    match automod::check_filter(target, &guild_data, &ctx).await {
+                        Ok(true) => {},
+                        Ok(false) => if let Err(why) = automod::check_links(target, &guild_data, &ctx).await {
+                            event!(Level::DEBUG, "Link filter failed in {}: {}", guild_id, why);
+                        },
+                        Err(why) => { event!(Level::DEBUG, "Filter failed in {}: {}", guild_id, why); },
+                    }
+                }
+            }
             if let Some(old_message) = old_message {
                 if let Some(guild_id) = message.guild_id {
                     let channel_name = cache.guild_channel(message.channel_id)
@@ -135,7 +234,7 @@ async fn handle_event(
                     match db.get_guild(guild_id.0 as i64) {
                         Ok(guild_data) => {
                             if guild_data.logging.contains(&String::from("message_edit")) { return Ok(()) }
-                            let audit_channel = ChannelId(guild_data.audit_channel as u64);
+                            let audit_channel = log_channel(&guild_data, "message_edit", guild_data.audit_channel);
                             let new_content = message.content.unwrap_or("".to_string());
                             let distance = levenshtein(old_message.content.as_str(), new_content.as_str());
                             if guild_data.audit && audit_channel.0 > 0 && distance >= guild_data.audit_threshold as usize {
@@ -193,9 +292,44 @@ async fn handle_event(
             }
         }
         Event::MemberAdd(member) => {
-            // TODO maybe hackbans still. Think about it
             match db.get_guild(member.guild_id.0 as i64) {
                 Ok(guild_data) => {
+                    // Hackbans that couldn't be applied through the API are enforced on join
+                    if let Ok(hackban) = db.get_hackban(member.user.id.0 as i64, member.guild_id.0 as i64) {
+                        let reason = hackban.reason.unwrap_or("No reason given".to_string());
+                        http.create_ban(member.guild_id, member.user.id)
+                            .reason(format!("Hackban: {}", reason))?
+                            .await?;
+                        // The BanAdd event skips bans made by the bot, so the case is recorded here
+                        let case = db.new_case(member.user.id.0 as i64,
+                            member.guild_id.0 as i64,
+                            "Ban".to_string(),
+                            Some(format!("Hackban: {}", reason)),
+                            ctx.user.id.0 as i64)?;
+                        let modlog_channel = log_channel(&guild_data, "member_ban", guild_data.modlog_channel);
+                        if guild_data.modlog && modlog_channel.0 > 0 {
+                            let embed = EmbedBuilder::new()
+                                .title("Hackbanned Member Banned")
+                                .color(colors::RED)
+                                .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
+                                .timestamp(Utc::now().to_rfc3339())
+                                .description(format!("**Member:** {}#{} ({}) - {}\n**Reason:** {}",
+                                    member.user.name,
+                                    member.user.discriminator,
+                                    member.user.id.0,
+                                    member.user.mention(),
+                                    reason
+                                ))
+                                .footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)))
+                                .build()?;
+                            let log = http.create_message(modlog_channel).embed(embed)?.await?;
+                            db.set_case_modlog(case.guild_id, case.case_number, log.channel_id.0 as i64, log.id.0 as i64)?;
+                        }
+                        return Ok(())
+                    }
+                    if let Err(why) = antiraid::check_join(&member, &guild_data, &ctx).await {
+                        event!(Level::WARN, "Raid protection failed in {}: {}", member.guild_id, why);
+                    }
                     if guild_data.logging.contains(&String::from("member_join")) { return Ok(()) }
                     let user_update = crate::db::models::UserUpdate {
                         id: member.user.id.0 as i64,
@@ -204,8 +338,8 @@ async fn handle_event(
                     };
                     match db.upsert_user(user_update) {
                         Ok(mut user_data) => {
-                            if guild_data.audit && guild_data.audit_channel > 0 {
-                                let audit_channel = ChannelId(guild_data.audit_channel as u64);
+                            let audit_channel = log_channel(&guild_data, "member_join", guild_data.audit_channel);
+                            if guild_data.audit && audit_channel.0 > 0 {
                                 let embed = EmbedBuilder::new()
                                     .title("Member Joined")
                                     .color(colors::GREEN)
@@ -244,10 +378,47 @@ async fn handle_event(
         Event::MemberRemove(member) => {
             match db.get_guild(member.guild_id.0 as i64) {
                 Ok(guild_data) => {
-                    db.del_user(member.user.id.0 as i64, member.guild_id.0 as i64);
+                    // Watchlisted users are kept so the flag still applies if they rejoin
+                    match db.get_user(member.user.id.0 as i64, member.guild_id.0 as i64) {
+                        Ok(user_data) if user_data.watchlist => {},
+                        _ => { db.del_user(member.user.id.0 as i64, member.guild_id.0 as i64); },
+                    }
+                    // Bans are logged by BanAdd
+                    if audit_entry(&ctx, member.guild_id, AuditLogEvent::MemberBanAdd, member.user.id.0).await.is_some() { return Ok(()) }
+                    if let Some(audit) = audit_entry(&ctx, member.guild_id, AuditLogEvent::MemberKick, member.user.id.0).await {
+                        // Kicks made through commands are already logged with a case
+                        if audit.user_id == Some(ctx.user.id) { return Ok(()) }
+                        let case = db.new_case(member.user.id.0 as i64,
+                            member.guild_id.0 as i64,
+                            "Kick".to_string(),
+                            audit.reason.clone(),
+                            audit.user_id.map(|u| u.0 as i64).unwrap_or(0))?;
+                        if guild_data.logging.contains(&String::from("member_kick")) { return Ok(()) }
+                        let modlog_channel = log_channel(&guild_data, "member_kick", guild_data.modlog_channel);
+                        if guild_data.modlog && modlog_channel.0 > 0 {
+                            let embed = EmbedBuilder::new()
+                                .title("Member Kicked")
+                                .color(colors::RED)
+                                .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
+                                .timestamp(Utc::now().to_rfc3339())
+                                .description(format!("**Member:** {}#{} ({}) - {}\n**Responsible Moderator:** {}\n**Reason:** {}",
+                                    member.user.name,
+                                    member.user.discriminator,
+                                    member.user.id.0,
+                                    member.user.mention(),
+                                    audit_user(&ctx, audit.user_id).await,
+                                    audit.reason.clone().unwrap_or("None".to_string())
+                                ))
+                                .footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)))
+                                .build()?;
+                            let log = http.create_message(modlog_channel).embed(embed)?.await?;
+                            db.set_case_modlog(case.guild_id, case.case_number, log.channel_id.0 as i64, log.id.0 as i64)?;
+                        }
+                        return Ok(())
+                    }
                     if guild_data.logging.contains(&String::from("member_leave")) { return Ok(()) }
-                    if guild_data.audit && guild_data.audit_channel > 0 {
-                        let audit_channel = ChannelId(guild_data.audit_channel as u64);
+                    let audit_channel = log_channel(&guild_data, "member_leave", guild_data.audit_channel);
+                    if guild_data.audit && audit_channel.0 > 0 {
                         let embed = EmbedBuilder::new()
                             .title("Member Left")
                             .color(colors::RED)
@@ -257,73 +428,107 @@ async fn handle_event(
                             .build()?;
                         http.create_message(audit_channel).embed(embed)?.await?;
                     }
-                    //TODO kick log
                 }
                 _ => {}
             }
         }
         Event::MemberUpdate(member) => {
+            if let Ok(mut user_data) = db.get_user(member.user.id.0 as i64, member.guild_id.0 as i64) {
+                let username = format!("{}#{}", member.user.name, member.user.discriminator);
+                if user_data.username != username {
+                    user_data.username = username;
+                    if let Err(why) = db.update_user(member.user.id.0 as i64, member.guild_id.0 as i64, user_data) {
+                        event!(Level::DEBUG, "Failed to sync username of {}: {}", member.user.id, why);
+                    }
+                }
+            }
             if let Some(old_member) = old_member {
                 match ctx.db.get_guild(member.guild_id.0 as i64) {
                     Ok(guild_data) => {
-                        if guild_data.audit && guild_data.audit_channel > 0 {
-                            let audit_channel = ChannelId(guild_data.audit_channel as u64);
-                            if !guild_data.logging.contains(&String::from("nickname_change")) {
-                                if member.nick != old_member.nick {
-                                    let old_nick = old_member.nick.clone().unwrap_or("None".to_string());
-                                    let new_nick = member.nick.clone().unwrap_or("None".to_string());
-                                    let embed = EmbedBuilder::new()
-                                        .title("Nickname Changed")
-                                        .color(colors::MAIN)
-                                        .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
-                                        .description(format!("**User: ** {}#{}\n**Old:** {}\n**New:** {}",
-                                            member.user.name,
-                                            member.user.discriminator,
-                                            old_nick,
-                                            new_nick
-                                        )).build()?;
+                        if guild_data.audit {
+                            // Discord sends a member update to every guild the user shares with us, and each
+                            // guild's cached member still holds the user as it was, so every guild logs the change
+                            let (old_user, new_user) = (&old_member.user, &member.user);
+                            let audit_channel = log_channel(&guild_data, "username_change", guild_data.audit_channel);
+                            if !guild_data.logging.contains(&String::from("username_change")) && audit_channel.0 > 0
+                                && (old_user.name != new_user.name || old_user.discriminator != new_user.discriminator || old_user.avatar != new_user.avatar) {
+                                let mut description = format!("**User:** {} ({})", new_user.mention(), new_user.id.0);
+                                if old_user.name != new_user.name || old_user.discriminator != new_user.discriminator {
+                                    description.push_str(&format!("\n**Old Username:** {}#{}\n**New Username:** {}#{}",
+                                        old_user.name,
+                                        old_user.discriminator,
+                                        new_user.name,
+                                        new_user.discriminator
+                                    ));
+                                }
+                                if old_user.avatar != new_user.avatar {
+                                    description.push_str(&format!("\n**Avatar:** [Old]({}) → [New]({})",
+                                        user_avatar_url(old_user),
+                                        user_avatar_url(new_user)
+                                    ));
+                                }
+                                let embed = EmbedBuilder::new()
+                                    .title("Profile Changed")
+                                    .color(colors::MAIN)
+                                    .thumbnail(ImageSource::url(user_avatar_url(new_user))?)
+                                    .timestamp(Utc::now().to_rfc3339())
+                                    .description(description)
+                                    .build()?;
 
-                                    ctx.http.create_message(audit_channel).embed(embed)?.await?;
+                                ctx.http.create_message(audit_channel).embed(embed)?.await?;
+                            }
+                            let audit_channel = log_channel(&guild_data, "nickname_change", guild_data.audit_channel);
+                            if !guild_data.logging.contains(&String::from("nickname_change")) && audit_channel.0 > 0 && member.nick != old_member.nick {
+                                let old_nick = old_member.nick.clone().unwrap_or("None".to_string());
+                                let new_nick = member.nick.clone().unwrap_or("None".to_string());
+                                let mut description = format!("**User: ** {}#{}\n**Old:** {}\n**New:** {}",
+                                    member.user.name,
+                                    member.user.discriminator,
+                                    old_nick,
+                                    new_nick
+                                );
+                                if let Some(audit) = audit_entry(&ctx, member.guild_id, AuditLogEvent::MemberUpdate, member.user.id.0).await {
+                                    description.push_str(&audit_attribution(&ctx, &audit).await);
                                 }
-                                if !guild_data.logging.contains(&String::from("role_change")) {
-                                    let mut roles_added = member.roles.clone();
-                                    roles_added.retain(|r| !old_member.roles.contains(r));
+                                let embed = EmbedBuilder::new()
+                                    .title("Nickname Changed")
+                                    .color(colors::MAIN)
+                                    .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
+                                    .description(description)
+                                    .build()?;
+
+                                ctx.http.create_message(audit_channel).embed(embed)?.await?;
+                            }
+                            let audit_channel = log_channel(&guild_data, "role_change", guild_data.audit_channel);
+                            if !guild_data.logging.contains(&String::from("role_change")) && audit_channel.0 > 0 {
+                                let role_name = |id: &RoleId| cache.role(*id).map(|r| r.name.clone()).unwrap_or(id.0.to_string());
+                                let roles_added = member.roles.iter()
+                                    .filter(|r| !old_member.roles.contains(r))
+                                    .map(role_name)
+                                    .collect::<Vec<String>>();
+                                let roles_removed = old_member.roles.iter()
+                                    .filter(|r| !member.roles.contains(r))
+                                    .map(role_name)
+                                    .collect::<Vec<String>>();
+                                if !roles_added.is_empty() || !roles_removed.is_empty() {
+                                    let mut description = format!("**User: ** {}#{}", member.user.name, member.user.discriminator);
                                     if !roles_added.is_empty() {
-                                        let guild_roles = ctx.http.roles(member.guild_id).await?;
-                                        let roles_added: Vec<String> = guild_roles.into_iter()
-                                            .filter_map(|role| if roles_added.contains(&role.id) { Some(role.name) } else { None })
-                                            .collect();
-                                        let embed = EmbedBuilder::new()
-                                            .title("Roles Changed")
-                                            .color(colors::MAIN)
-                                            .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
-                                            .description(format!("**User: ** {}#{}\n**Added:** {}",
-                                                member.user.name,
-                                                member.user.discriminator,
-                                                roles_added.join(", ")
-                                            )).build()?;
-                                        
-                                        ctx.http.create_message(audit_channel).embed(embed)?.await?;
+                                        description.push_str(&format!("\n**Added:** {}", roles_added.join(", ")));
                                     }
-                                    let mut roles_removed = old_member.roles.clone();
-                                    roles_removed.retain(|r| !member.roles.contains(r));
                                     if !roles_removed.is_empty() {
-                                        let guild_roles = ctx.http.roles(member.guild_id).await?;
-                                        let roles_removed: Vec<String> = guild_roles.into_iter()
-                                            .filter_map(|role| if roles_removed.contains(&role.id) { Some(role.name) } else { None })
-                                            .collect();
-                                        let embed = EmbedBuilder::new()
-                                            .title("Roles Changed")
-                                            .color(colors::MAIN)
-                                            .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
-                                            .description(format!("**User: ** {}#{}\n**Removed:** {}",
-                                                member.user.name,
-                                                member.user.discriminator,
-                                                roles_removed.join(", ")
-                                            )).build()?;
-                                        
-                                        ctx.http.create_message(audit_channel).embed(embed)?.await?;
+                                        description.push_str(&format!("\n**Removed:** {}", roles_removed.join(", ")));
                                     }
+                                    if let Some(audit) = audit_entry(&ctx, member.guild_id, AuditLogEvent::MemberRoleUpdate, member.user.id.0).await {
+                                        description.push_str(&audit_attribution(&ctx, &audit).await);
+                                    }
+                                    let embed = EmbedBuilder::new()
+                                        .title("Roles Changed")
+                                        .color(colors::MAIN)
+                                        .thumbnail(ImageSource::url(user_avatar_url(&member.user))?)
+                                        .description(description)
+                                        .build()?;
+
+                                    ctx.http.create_message(audit_channel).embed(embed)?.await?;
                                 }
                             }
                         }
@@ -333,20 +538,27 @@ async fn handle_event(
             }
         }
         Event::BanAdd(ban) => {
-            use twilight_model::guild::audit_log::AuditLogEvent;
             let audit_request = ctx.http.audit_log(ban.guild_id)
                 .action_type(AuditLogEvent::MemberBanAdd)
                 .limit(1)?;
             if let Some(audit_log) = audit_request.await? {
                 if let Some(audit) = audit_log.audit_log_entries.first() {
+                    // Bans made through commands are already logged with a case
+                    if audit.user_id == Some(ctx.user.id) { return Ok(()) }
                     match ctx.db.get_guild(ban.guild_id.0 as i64) {
                         Ok(guild_data) => {
-                            if guild_data.logging.contains(&String::from("member_ban")) { return Ok(()) }
                             let target_id = audit.target_id.clone()
                                 .map(|ref s| UserId(s.parse::<u64>().unwrap_or(0)))
                                 .unwrap();
-                            if guild_data.modlog && guild_data.modlog_channel > 0 && target_id == ban.user.id {
-                                let modlog_channel = ChannelId(guild_data.modlog_channel as u64);
+                            if target_id != ban.user.id { return Ok(()) }
+                            let case = db.new_case(ban.user.id.0 as i64,
+                                ban.guild_id.0 as i64,
+                                "Ban".to_string(),
+                                audit.reason.clone(),
+                                audit.user_id.map(|u| u.0 as i64).unwrap_or(0))?;
+                            if guild_data.logging.contains(&String::from("member_ban")) { return Ok(()) }
+                            let modlog_channel = log_channel(&guild_data, "member_ban", guild_data.modlog_channel);
+                            if guild_data.modlog && modlog_channel.0 > 0 {
                                 let moderator = match audit.user_id {
                                     Some(user_id) => { match ctx.http.user(user_id).await? {
                                         Some(user) => { format!("{}#{} ({})", user.name, user.discriminator, user.id.0) }
@@ -366,8 +578,11 @@ async fn handle_event(
                                         ban.user.mention(),
                                         moderator,
                                         audit.reason.clone().unwrap_or("None".to_string())
-                                    )).build()?;
-                                ctx.http.create_message(modlog_channel).embed(embed)?.await?;
+                                    ))
+                                    .footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)))
+                                    .build()?;
+                                let log = ctx.http.create_message(modlog_channel).embed(embed)?.await?;
+                                db.set_case_modlog(case.guild_id, case.case_number, log.channel_id.0 as i64, log.id.0 as i64)?;
                             }
                         }
                         _ => {}
@@ -376,7 +591,6 @@ async fn handle_event(
             }
         }
         Event::BanRemove(ban) => {
-            use twilight_model::guild::audit_log::AuditLogEvent;
             let audit_request = ctx.http.audit_log(ban.guild_id)
                 .action_type(AuditLogEvent::MemberBanRemove)
                 .limit(1)?;
@@ -388,8 +602,8 @@ async fn handle_event(
                             let target_id = audit.target_id.clone()
                                 .map(|ref s| UserId(s.parse::<u64>().unwrap_or(0)))
                                 .unwrap();
-                            if guild_data.modlog && guild_data.modlog_channel > 0 && target_id == ban.user.id {
-                                let modlog_channel = ChannelId(guild_data.modlog_channel as u64);
+                            let modlog_channel = log_channel(&guild_data, "member_unban", guild_data.modlog_channel);
+                            if guild_data.modlog && modlog_channel.0 > 0 && target_id == ban.user.id {
                                 let moderator = match audit.user_id {
                                     Some(user_id) => { match ctx.http.user(user_id).await? {
                                         Some(user) => { format!("{}#{} ({})", user.name, user.discriminator, user.id.0) }
@@ -409,7 +623,8 @@ async fn handle_event(
                                         ban.user.mention(),
                                         moderator,
                                         audit.reason.clone().unwrap_or("None".to_string())
-                                    )).build()?;
+                                    ))
+                                    .build()?;
                                 ctx.http.create_message(modlog_channel).embed(embed)?.await?;
                             }
                         }
@@ -424,8 +639,507 @@ async fn handle_event(
         Event::Resumed => {
             event!(Level::DEBUG, "Session resumed");
         }
+        Event::ChannelCreate(_)
+        | Event::ChannelUpdate(_)
+        | Event::ChannelDelete(_)
+        | Event::RoleCreate(_)
+        | Event::RoleUpdate(_)
+        | Event::RoleDelete(_)
+        | Event::GuildEmojisUpdate(_) => {
+            log_guild_change(&event, &ctx, previous).await?;
+        }
+        Event::VoiceStateUpdate(update) => {
+            log_voice_change(&update.0, &ctx, previous).await?;
+        }
+        Event::MessageDeleteBulk(delete) => {
+            log_bulk_delete(&delete, &ctx, previous).await?;
+        }
         _ => { #[cfg(debug_assertions)] { event!(Level::DEBUG, "Unhandled event: {:?}", event.kind()); }}
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+/// Finds the latest audit log entry of the given kind targeting `target`, as long as it was made within
+/// the last `AUDIT_WINDOW` seconds. Gateway events don't say who caused them, so this is how they are attributed.
+async fn audit_entry(ctx: &Context, guild_id: GuildId, kind: AuditLogEvent, target: u64) -> Option<AuditLogEntry> {
+    let request = ctx.http.audit_log(guild_id)
+        .action_type(kind)
+        .limit(AUDIT_ENTRIES)
+        .ok()?;
+    let audit_log = request.await.ok().flatten()?;
+    let now = Utc::now();
+    let target = target.to_string();
+    audit_log.audit_log_entries.into_iter()
+        .find(|entry| entry.target_id.as_ref() == Some(&target)
+            && (now - snowflake_time(entry.id.0)).num_seconds() <= AUDIT_WINDOW)
+}
+
+/// Formats the user responsible for an audit log entry
+async fn audit_user(ctx: &Context, user_id: Option<UserId>) -> String {
+    let user_id = match user_id {
+        Some(user_id) => user_id,
+        None => return "unknown".to_string(),
+    };
+    if let Some(user) = ctx.cache.user(user_id) {
+        return format!("{}#{} ({})", user.name, user.discriminator, user.id.0);
+    }
+    match ctx.http.user(user_id).await {
+        Ok(Some(user)) => format!("{}#{} ({})", user.name, user.discriminator, user.id.0),
+        _ => format!("unknown ({})", user_id.0),
+    }
+}
+
+/// The responsible user and reason of an audit log entry, as lines to append to a log entry
+async fn audit_attribution(ctx: &Context, audit: &AuditLogEntry) -> String {
+    let mut lines = format!("\n**Responsible User:** {}", audit_user(ctx, audit.user_id).await);
+    if let Some(reason) = &audit.reason {
+        lines.push_str(&format!("\n**Reason:** {}", reason));
+    }
+    lines
+}
+
+/// Logs channels, roles and emojis being created, updated or deleted to the audit channel, along with who did it
+async fn log_guild_change(event: &Event, ctx: &Context, previous: Option<Previous>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = match event {
+        Event::ChannelCreate(ChannelCreate(Channel::Guild(channel)))
+        | Event::ChannelUpdate(ChannelUpdate(Channel::Guild(channel)))
+        | Event::ChannelDelete(ChannelDelete(Channel::Guild(channel))) => channel.guild_id(),
+        Event::RoleCreate(create) => Some(create.guild_id),
+        Event::RoleUpdate(update) => Some(update.guild_id),
+        Event::RoleDelete(delete) => Some(delete.guild_id),
+        Event::GuildEmojisUpdate(update) => Some(update.guild_id),
+        _ => None,
+    };
+    let guild_id = match guild_id {
+        Some(guild_id) => guild_id,
+        None => { return Ok(()) },
+    };
+    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if !guild_data.audit { return Ok(()) }
+
+    let (log_type, title, color, target, actions, details) = match (event, previous) {
+        (Event::ChannelCreate(ChannelCreate(Channel::Guild(channel))), _) => {
+            ("channel_create", "Channel Created", colors::GREEN, channel.id().0, vec![AuditLogEvent::ChannelCreate],
+                format!("**Channel:** {} ({})\n**Type:** {}", channel.id().mention(), channel.id().0, channel_kind(channel)))
+        },
+        (Event::ChannelUpdate(ChannelUpdate(Channel::Guild(channel))), Some(Previous::Channel(old))) => {
+            let changes = channel_changes(guild_id, &old, channel, ctx);
+            if changes.is_empty() { return Ok(()) }
+            ("channel_update", "Channel Updated", colors::MAIN, channel.id().0,
+                vec![AuditLogEvent::ChannelUpdate, AuditLogEvent::ChannelOverwriteCreate, AuditLogEvent::ChannelOverwriteUpdate, AuditLogEvent::ChannelOverwriteDelete],
+                format!("**Channel:** {} ({})\n{}", channel.id().mention(), channel.id().0, changes.join("\n")))
+        },
+        (Event::ChannelDelete(ChannelDelete(Channel::Guild(channel))), _) => {
+            ("channel_delete", "Channel Deleted", colors::RED, channel.id().0, vec![AuditLogEvent::ChannelDelete],
+                format!("**Channel:** #{} ({})\n**Type:** {}", channel.name(), channel.id().0, channel_kind(channel)))
+        },
+        (Event::RoleCreate(create), _) => {
+            ("role_create", "Role Created", colors::GREEN, create.role.id.0, vec![AuditLogEvent::RoleCreate],
+                format!("**Role:** {} ({})", create.role.id.mention(), create.role.id.0))
+        },
+        (Event::RoleUpdate(update), Some(Previous::Role(old))) => {
+            let changes = role_changes(&old, &update.role);
+            if changes.is_empty() { return Ok(()) }
+            ("role_update", "Role Updated", colors::MAIN, update.role.id.0, vec![AuditLogEvent::RoleUpdate],
+                format!("**Role:** {} ({})\n{}", update.role.id.mention(), update.role.id.0, changes.join("\n")))
+        },
+        (Event::RoleDelete(delete), previous) => {
+            let name = match previous {
+                Some(Previous::Role(old)) => old.name.clone(),
+                _ => "Unknown".to_string(),
+            };
+            ("role_delete", "Role Deleted", colors::RED, delete.role_id.0, vec![AuditLogEvent::RoleDelete],
+                format!("**Role:** {} ({})", name, delete.role_id.0))
+        },
+        (Event::GuildEmojisUpdate(update), Some(Previous::Emojis(old))) => {
+            let mut changes = Vec::new();
+            let mut target = None;
+            for emoji in update.emojis.iter() {
+                match old.iter().find(|e| e.id == emoji.id) {
+                    Some(e) if e.name != emoji.name => {
+                        changes.push(format!("**Renamed:** {} `{}` → `{}`", emoji_text(emoji.id, &emoji.name, emoji.animated), e.name, emoji.name));
+                        target = target.or(Some((emoji.id.0, AuditLogEvent::EmojiUpdate)));
+                    },
+                    Some(_) => {},
+                    None => {
+                        changes.push(format!("**Added:** {} `{}`", emoji_text(emoji.id, &emoji.name, emoji.animated), emoji.name));
+                        target = target.or(Some((emoji.id.0, AuditLogEvent::EmojiCreate)));
+                    },
+                }
+            }
+            for e in old.iter().filter(|e| !update.emojis.iter().any(|emoji| emoji.id == e.id)) {
+                changes.push(format!("**Removed:** `{}` ({})", e.name, e.id.0));
+                target = target.or(Some((e.id.0, AuditLogEvent::EmojiDelete)));
+            }
+            let (target, action) = match target {
+                Some(target) => target,
+                None => { return Ok(()) },
+            };
+            ("emoji_change", "Emojis Changed", colors::MAIN, target, vec![action], changes.join("\n"))
+        },
+        _ => { return Ok(()) },
+    };
+    let audit_channel = log_channel(&guild_data, log_type, guild_data.audit_channel);
+    if guild_data.logging.contains(&log_type.to_string()) || audit_channel.0 == 0 { return Ok(()) }
+
+    let mut description = details;
+    for action in actions {
+        if let Some(audit) = audit_entry(ctx, guild_id, action, target).await {
+            description.push_str(&audit_attribution(ctx, &audit).await);
+            break;
+        }
+    }
+    let embed = EmbedBuilder::new()
+        .title(title)
+        .color(color)
+        .description(description)
+        .timestamp(Utc::now().to_rfc3339())
+        .build()?;
+    ctx.http.create_message(audit_channel).embed(embed)?.await?;
+
+    Ok(())
+}
+
+/// Logs members joining, leaving and moving between voice channels, and being server muted or deafened
+async fn log_voice_change(state: &VoiceState, ctx: &Context, previous: Option<Previous>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = match state.guild_id {
+        Some(guild_id) => guild_id,
+        None => { return Ok(()) },
+    };
+    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if !guild_data.audit { return Ok(()) }
+    let old = match previous {
+        Some(Previous::Voice(old)) => Some(old),
+        _ => None,
+    };
+    let enabled = |log_type: &str| !guild_data.logging.contains(&log_type.to_string())
+        && log_channel(&guild_data, log_type, guild_data.audit_channel).0 > 0;
+
+    let user = match &state.member {
+        Some(member) => format!("{}#{}", member.user.name, member.user.discriminator),
+        None => ctx.cache.user(state.user_id).map(|u| format!("{}#{}", u.name, u.discriminator)).unwrap_or("Unknown".to_string()),
+    };
+    let user = format!("**User:** {} ({}) - {}", user, state.user_id.0, state.user_id.mention());
+    let mut embeds = Vec::new();
+
+    let movement = match (old.as_ref().and_then(|o| o.channel_id), state.channel_id) {
+        (None, Some(channel_id)) if enabled("voice_join") => Some(("voice_join", "Voice Channel Joined", colors::GREEN, format!("**Channel:** {}", channel_id.mention()))),
+        (Some(channel_id), None) if enabled("voice_leave") => Some(("voice_leave", "Voice Channel Left", colors::RED, format!("**Channel:** {}", channel_id.mention()))),
+        (Some(from), Some(to)) if from != to && enabled("voice_move") => Some(("voice_move", "Voice Channel Moved", colors::MAIN, format!("**From:** {}\n**To:** {}", from.mention(), to.mention()))),
+        _ => None,
+    };
+    if let Some((log_type, title, color, details)) = movement {
+        embeds.push((log_type, EmbedBuilder::new()
+            .title(title)
+            .color(color)
+            .description(format!("{}\n{}", user, details))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?));
+    }
+
+    if let Some(old) = old.as_ref().filter(|_| state.channel_id.is_some()) {
+        let mut changes = Vec::new();
+        let mut log_type = "voice_deafen";
+        if old.mute != state.mute && enabled("voice_mute") {
+            log_type = "voice_mute";
+            changes.push(change("Server Muted", old.mute, state.mute));
+        }
+        if old.deaf != state.deaf && enabled("voice_deafen") {
+            changes.push(change("Server Deafened", old.deaf, state.deaf));
+        }
+        if !changes.is_empty() {
+            let mut description = format!("{}\n{}", user, changes.join("\n"));
+            if let Some(audit) = audit_entry(ctx, guild_id, AuditLogEvent::MemberUpdate, state.user_id.0).await {
+                description.push_str(&audit_attribution(ctx, &audit).await);
+            }
+            embeds.push((log_type, EmbedBuilder::new()
+                .title("Voice State Changed")
+                .color(colors::YELLOW)
+                .description(description)
+                .timestamp(Utc::now().to_rfc3339())
+                .build()?));
+        }
+    }
+
+    for (log_type, embed) in embeds {
+        ctx.http.create_message(log_channel(&guild_data, log_type, guild_data.audit_channel)).embed(embed)?.await?;
+    }
+
+    Ok(())
+}
+
+/// Logs a bulk deletion as a single entry, with a transcript of the messages that were cached.
+/// Prunes made with our own command are skipped as `prune` posts its own log.
+async fn log_bulk_delete(delete: &MessageDeleteBulk, ctx: &Context, previous: Option<Previous>) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let guild_id = match delete.guild_id {
+        Some(guild_id) => guild_id,
+        None => { return Ok(()) },
+    };
+    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if guild_data.logging.contains(&String::from("message_delete")) { return Ok(()) }
+    let audit_channel = log_channel(&guild_data, "message_delete", guild_data.audit_channel);
+    if !guild_data.audit || audit_channel.0 == 0 { return Ok(()) }
+    let audit = audit_entry(ctx, guild_id, AuditLogEvent::MessageBulkDelete, delete.channel_id.0).await;
+
+    let mut cached = match previous {
+        Some(Previous::Messages(messages)) => messages,
+        _ => Vec::new(),
+    };
+    cached.sort_by_key(|m| m.id.0);
+    let mut uncached = delete.ids.iter()
+        .filter(|id| !cached.iter().any(|m| m.id == **id))
+        .map(|id| id.0)
+        .collect::<Vec<u64>>();
+    uncached.sort();
+
+    let channel_name = ctx.cache.guild_channel(delete.channel_id).map(|c| c.name().to_string()).unwrap_or("unknown".to_string());
+    let mut transcript = vec![format!("{} messages deleted from #{} ({})", delete.ids.len(), channel_name, delete.channel_id.0)];
+    if !cached.is_empty() {
+        transcript.push(String::new());
+        for message in cached.iter() {
+            let author = ctx.cache.user(message.author).map(user_tag).unwrap_or("Unknown".to_string());
+            let mut line = format!("[{}] {} ({}): {}", message.timestamp, author, message.author.0, message.content);
+            for attachment in message.attachments.iter() {
+                line.push_str(format!("\r\n    Attachment: {}", attachment.url).as_str());
+            }
+            transcript.push(line);
+        }
+    }
+    if !uncached.is_empty() {
+        transcript.push(String::new());
+        transcript.push(String::from("Uncached message IDs:"));
+        transcript.extend(uncached.iter().map(|id| id.to_string()));
+    }
+
+    let mut description = format!("**Channel:** {} ({}) - {}\n**Count:** {}\n**Cached:** {}\n**Uncached:** {}",
+        channel_name,
+        delete.channel_id.0,
+        delete.channel_id.mention(),
+        delete.ids.len(),
+        cached.len(),
+        uncached.len());
+    if let Some(audit) = &audit {
+        description.push_str(&audit_attribution(ctx, audit).await);
+    }
+    let embed = EmbedBuilder::new()
+        .title("Messages Bulk Deleted")
+        .color(colors::RED)
+        .description(description)
+        .timestamp(Utc::now().to_rfc3339())
+        .build()?;
+    ctx.http.create_message(audit_channel)
+        .embed(embed)?
+        .file(format!("bulk-delete-{}.txt", Utc::now().format("%FT%T")).as_str(), transcript.join("\r\n").as_bytes())
+        .await?;
+
+    Ok(())
+}
+
+/// A readable name for the kind of a guild channel
+fn channel_kind(channel: &GuildChannel) -> &'static str {
+    match channel {
+        GuildChannel::Category(_) => "Category",
+        GuildChannel::Text(_) => "Text",
+        GuildChannel::Voice(_) => "Voice",
+        #[allow(unreachable_patterns)]
+        _ => "Other",
+    }
+}
+
+/// Formats a before and after line for a log entry
+fn change<T: std::fmt::Display>(field: &str, old: T, new: T) -> String {
+    format!("**{}:** {} → {}", field, old, new)
+}
+
+/// Formats a set of permissions, or "None"
+fn permission_list(permissions: Permissions) -> String {
+    if permissions.is_empty() { "None".to_string() } else { format!("{:?}", permissions) }
+}
+
+/// The changes between two versions of a channel: name, topic, slowmode, NSFW, category, voice settings
+/// and permission overwrites
+fn channel_changes(guild_id: GuildId, old: &GuildChannel, new: &GuildChannel, ctx: &Context) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.name() != new.name() {
+        changes.push(change("Name", old.name(), new.name()));
+    }
+    let category = |id: Option<ChannelId>| id.and_then(|id| ctx.cache.guild_channel(id)).map(|c| c.name().to_string()).unwrap_or("None".to_string());
+    match (old, new) {
+        (GuildChannel::Text(old), GuildChannel::Text(new)) => {
+            if old.topic != new.topic {
+                changes.push(change("Topic", old.topic.clone().unwrap_or("None".to_string()), new.topic.clone().unwrap_or("None".to_string())));
+            }
+            if old.rate_limit_per_user != new.rate_limit_per_user {
+                changes.push(change("Slowmode", format!("{}s", old.rate_limit_per_user.unwrap_or(0)), format!("{}s", new.rate_limit_per_user.unwrap_or(0))));
+            }
+            if old.nsfw != new.nsfw {
+                changes.push(change("NSFW", old.nsfw, new.nsfw));
+            }
+            if old.parent_id != new.parent_id {
+                changes.push(change("Category", category(old.parent_id), category(new.parent_id)));
+            }
+        },
+        (GuildChannel::Voice(old), GuildChannel::Voice(new)) => {
+            if old.bitrate != new.bitrate {
+                changes.push(change("Bitrate", old.bitrate, new.bitrate));
+            }
+            if old.user_limit != new.user_limit {
+                changes.push(change("User Limit", old.user_limit.unwrap_or(0), new.user_limit.unwrap_or(0)));
+            }
+            if old.parent_id != new.parent_id {
+                changes.push(change("Category", category(old.parent_id), category(new.parent_id)));
+            }
+        },
+        _ => {},
+    }
+
+    let target = |kind: &PermissionOverwriteType| match kind {
+        PermissionOverwriteType::Role(id) if id.0 == guild_id.0 => "@everyone".to_string(),
+        PermissionOverwriteType::Role(id) => ctx.cache.role(*id).map(|r| format!("@{}", r.name)).unwrap_or(id.0.to_string()),
+        PermissionOverwriteType::Member(id) => id.mention().to_string(),
+    };
+    let (old, new) = (lockdown::channel_overwrites(old), lockdown::channel_overwrites(new));
+    for overwrite in new.iter() {
+        match old.iter().find(|o| o.kind == overwrite.kind) {
+            Some(o) if o.allow == overwrite.allow && o.deny == overwrite.deny => {},
+            Some(o) => {
+                let mut diff = Vec::new();
+                let allowed = overwrite.allow - o.allow;
+                let denied = overwrite.deny - o.deny;
+                let reset = (o.allow | o.deny) - (overwrite.allow | overwrite.deny);
+                if !allowed.is_empty() { diff.push(format!("allowed {}", permission_list(allowed))); }
+                if !denied.is_empty() { diff.push(format!("denied {}", permission_list(denied))); }
+                if !reset.is_empty() { diff.push(format!("reset {}", permission_list(reset))); }
+                changes.push(format!("**Overwrite Changed:** {}: {}", target(&overwrite.kind), diff.join(", ")));
+            },
+            None => {
+                changes.push(format!("**Overwrite Added:** {}: allowed {}, denied {}", target(&overwrite.kind), permission_list(overwrite.allow), permission_list(overwrite.deny)));
+            },
+        }
+    }
+    for overwrite in old.iter().filter(|o| !new.iter().any(|n| n.kind == o.kind)) {
+        changes.push(format!("**Overwrite Removed:** {}", target(&overwrite.kind)));
+    }
+
+    changes
+}
+
+/// The changes between two versions of a role: name, colour, hoist, mentionable and permissions
+fn role_changes(old: &Role, new: &Role) -> Vec<String> {
+    let mut changes = Vec::new();
+    if old.name != new.name {
+        changes.push(change("Name", &old.name, &new.name));
+    }
+    if old.color != new.color {
+        changes.push(change("Colour", format!("#{:06x}", old.color), format!("#{:06x}", new.color)));
+    }
+    if old.hoist != new.hoist {
+        changes.push(change("Hoisted", old.hoist, new.hoist));
+    }
+    if old.mentionable != new.mentionable {
+        changes.push(change("Mentionable", old.mentionable, new.mentionable));
+    }
+    let granted = new.permissions - old.permissions;
+    let revoked = old.permissions - new.permissions;
+    if !granted.is_empty() {
+        changes.push(format!("**Permissions Granted:** {}", permission_list(granted)));
+    }
+    if !revoked.is_empty() {
+        changes.push(format!("**Permissions Revoked:** {}", permission_list(revoked)));
+    }
+
+    changes
+}
+
+/// Formats an emoji so that it renders in a message
+fn emoji_text(id: EmojiId, name: &str, animated: bool) -> String {
+    format!("<{}:{}:{}>", if animated { "a" } else { "" }, name, id.0)
+}
+
+/// Mirrors messages, edits, deletions, joins and role changes of watchlisted users into the guild's watchlist channel
+async fn mirror_watchlist(
+    event: &Event,
+    ctx: &Context,
+    old_message: &Option<Arc<CachedMessage>>,
+    old_member: &Option<Arc<CachedMember>>,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (guild_id, user_id) = match (event, old_message) {
+        (Event::MessageCreate(message), _) => (message.guild_id, message.author.id),
+        (Event::MessageUpdate(message), Some(old)) => (message.guild_id, old.author),
+        (Event::MessageDelete(message), Some(old)) => (message.guild_id, old.author),
+        (Event::MemberAdd(member), _) => (Some(member.guild_id), member.user.id),
+        (Event::MemberUpdate(member), _) => (Some(member.guild_id), member.user.id),
+        _ => { return Ok(()) },
+    };
+    let guild_id = match guild_id {
+        Some(guild_id) => guild_id,
+        None => { return Ok(()) },
+    };
+    let guild_data = ctx.db.get_guild(guild_id.0 as i64)?;
+    if guild_data.watchlist_channel <= 0 { return Ok(()) }
+    match ctx.db.get_user(user_id.0 as i64, guild_id.0 as i64) {
+        Ok(user_data) if user_data.watchlist => {},
+        _ => { return Ok(()) },
+    }
+
+    let (title, color, details) = match event {
+        Event::MessageCreate(message) => {
+            let mut details = format!("**Channel:** <#{}>\n**Content:**\n{}", message.channel_id.0, message.content);
+            if !message.attachments.is_empty() {
+                details.push_str(format!("\n**Attachments:**\n{}", message.attachments.iter()
+                    .map(|a| a.url.clone())
+                    .collect::<Vec<String>>()
+                    .join("\n")).as_str());
+            }
+            ("Message Sent", colors::MAIN, details)
+        },
+        Event::MessageUpdate(message) => {
+            let old = match old_message {
+                Some(old) => old,
+                None => { return Ok(()) },
+            };
+            let new_content = match &message.content {
+                Some(content) if *content != old.content => content.clone(),
+                _ => { return Ok(()) },
+            };
+            ("Message Edited", colors::MAIN, format!("**Channel:** <#{}>\n**Old Content:**\n{}\n**New Content:**\n{}", message.channel_id.0, old.content, new_content))
+        },
+        Event::MessageDelete(message) => {
+            let old = match old_message {
+                Some(old) => old,
+                None => { return Ok(()) },
+            };
+            ("Message Deleted", colors::RED, format!("**Channel:** <#{}>\n**Content:**\n{}", message.channel_id.0, old.content))
+        },
+        Event::MemberAdd(_) => ("Joined", colors::GREEN, String::new()),
+        Event::MemberUpdate(member) => {
+            let old = match old_member {
+                Some(old) => old,
+                None => { return Ok(()) },
+            };
+            let role_name = |id: &RoleId| ctx.cache.role(*id).map(|r| r.name.clone()).unwrap_or(id.0.to_string());
+            let added = member.roles.iter().filter(|r| !old.roles.contains(r)).map(role_name).collect::<Vec<String>>();
+            let removed = old.roles.iter().filter(|r| !member.roles.contains(r)).map(role_name).collect::<Vec<String>>();
+            if added.is_empty() && removed.is_empty() { return Ok(()) }
+            let mut details = Vec::new();
+            if !added.is_empty() { details.push(format!("**Added:** {}", added.join(", "))); }
+            if !removed.is_empty() { details.push(format!("**Removed:** {}", removed.join(", "))); }
+            ("Roles Changed", colors::BLUE, details.join("\n"))
+        },
+        _ => { return Ok(()) },
+    };
+
+    let tag = match ctx.cache.user(user_id) {
+        Some(user) => format!("{}#{}", user.name, user.discriminator),
+        None => "Unknown".to_string(),
+    };
+    let embed = EmbedBuilder::new()
+        .title(format!("Watchlist: {}", title))
+        .color(color)
+        .description(format!("**User:** {} ({}) - {}\n{}", tag, user_id.0, user_id.mention(), details))
+        .timestamp(Utc::now().to_rfc3339())
+        .build()?;
+    ctx.http.create_message(ChannelId(guild_data.watchlist_channel as u64)).embed(embed)?.await?;
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+//! Locking channels by denying SEND_MESSAGES to @everyone. The previous overwrites are stored so that
+//! unlocking puts back exactly what was there before.
+use crate::Context;
+use crate::core::timers::TimerPayload;
+use crate::db::DatabaseConnection;
+use std::error::Error;
+use twilight_http::Client as HttpClient;
+use twilight_model::channel::GuildChannel;
+use twilight_model::channel::permission_overwrite::{PermissionOverwrite, PermissionOverwriteType};
+use twilight_model::guild::Permissions;
+use twilight_model::id::{ChannelId, GuildId, RoleId};
+
+/// The permission overwrites of a guild channel
+pub fn channel_overwrites(channel: &GuildChannel) -> Vec<PermissionOverwrite> {
+    match channel {
+        GuildChannel::Category(c) => c.permission_overwrites.clone(),
+        GuildChannel::Text(c) => c.permission_overwrites.clone(),
+        GuildChannel::Voice(c) => c.permission_overwrites.clone(),
+        #[allow(unreachable_patterns)]
+        _ => Vec::new(),
+    }
+}
+
+/// The text channels of a guild, from the cache
+pub fn text_channels(guild_id: GuildId, ctx: &Context) -> Vec<ChannelId> {
+    ctx.cache.guild_channels(guild_id)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|id| matches!(ctx.cache.guild_channel(*id).as_deref(), Some(GuildChannel::Text(_))))
+        .collect()
+}
+
+/// The @everyone overwrite among a channel's overwrites, as (allow, deny)
+fn everyone_overwrite(guild_id: GuildId, overwrites: &[PermissionOverwrite]) -> Option<(Permissions, Permissions)> {
+    overwrites.iter()
+        .find(|o| o.kind == PermissionOverwriteType::Role(RoleId(guild_id.0)))
+        .map(|o| (o.allow, o.deny))
+}
+
+/// Denies SEND_MESSAGES to @everyone in a channel, storing its current overwrites first.
+/// `raid` marks locks made by raid protection, which `antiraid off` lifts.
+/// Returns false if the channel is already locked.
+pub async fn lock_channel(ctx: &Context, guild_id: GuildId, channel_id: ChannelId, raid: bool) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let overwrites = match ctx.cache.guild_channel(channel_id) {
+        Some(channel) => channel_overwrites(&channel),
+        None => return Err("Channel not found in cache".into()),
+    };
+    let snapshot = serde_json::to_value(&overwrites)?;
+    if ctx.db.new_channel_lock(channel_id.0 as i64, guild_id.0 as i64, snapshot, raid)?.is_none() {
+        return Ok(false);
+    }
+
+    let (allow, deny) = everyone_overwrite(guild_id, &overwrites).unwrap_or((Permissions::empty(), Permissions::empty()));
+    let result = ctx.http.update_channel_permission(channel_id, allow - Permissions::SEND_MESSAGES, deny | Permissions::SEND_MESSAGES)
+        .role(RoleId(guild_id.0))
+        .await;
+    if let Err(why) = result {
+        ctx.db.del_channel_lock(channel_id.0 as i64)?;
+        return Err(why.into());
+    }
+
+    Ok(true)
+}
+
+/// Puts back the @everyone overwrite a channel had before it was locked and cancels any pending unlock timer.
+/// Returns false if the channel isn't locked.
+pub async fn unlock_channel(ctx: &Context, guild_id: GuildId, channel_id: ChannelId) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    if !restore_channel(&ctx.http, &ctx.db, guild_id, channel_id).await? {
+        return Ok(false);
+    }
+    for timer in ctx.db.get_guild_timers(guild_id.0 as i64)? {
+        if let Ok(TimerPayload::Unlock { channel_id: c, .. }) = TimerPayload::from_timer(&timer) {
+            if c == channel_id { ctx.tc.cancel(timer.id)?; }
+        }
+    }
+
+    Ok(true)
+}
+
+/// Restores the overwrites stored for a locked channel. This is what `unlock_channel` and unlock timers use.
+/// The stored overwrites are only removed once they have been put back, so a failed unlock can be retried.
+/// Returns false if the channel isn't locked.
+pub async fn restore_channel(http: &HttpClient, db: &DatabaseConnection, guild_id: GuildId, channel_id: ChannelId) -> Result<bool, Box<dyn Error + Send + Sync>> {
+    let lock = match db.get_channel_lock(channel_id.0 as i64) {
+        Ok(lock) => lock,
+        Err(_) => return Ok(false),
+    };
+    let overwrites: Vec<PermissionOverwrite> = serde_json::from_value(lock.overwrites).unwrap_or_default();
+    match everyone_overwrite(guild_id, &overwrites) {
+        Some((allow, deny)) => {
+            http.update_channel_permission(channel_id, allow, deny)
+                .role(RoleId(guild_id.0))
+                .await?;
+        },
+        None => {
+            http.delete_channel_permission(channel_id).role(RoleId(guild_id.0)).await?;
+        },
+    }
+    db.del_channel_lock(channel_id.0 as i64)?;
+
+    Ok(true)
+}
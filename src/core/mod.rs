@@ -1,4 +1,9 @@
+pub mod antiraid;
+pub mod automod;
 pub mod handler;
+pub mod lockdown;
+pub mod moderation;
 pub mod consts;
+pub mod recurrence;
 pub mod timers;
 pub mod utils;
\ No newline at end of file
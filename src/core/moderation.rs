@@ -0,0 +1,374 @@
+use crate::Context;
+use crate::core::consts::*;
+use crate::core::lockdown;
+use crate::core::timers::TimerPayload;
+use crate::core::utils::*;
+use crate::db::models::{Case, Guild, WarnRule};
+use chrono::{Duration, Utc};
+use std::error::Error;
+use tracing::{event, Level};
+use std::sync::Arc;
+use twilight_mention::Mention;
+use twilight_embed_builder::{EmbedBuilder, EmbedFooterBuilder, ImageSource};
+use twilight_http::request::AuditLogReason;
+use twilight_model::channel::Message;
+use twilight_model::channel::embed::Embed;
+use twilight_model::guild::Role;
+use twilight_model::id::{ChannelId, GuildId, UserId};
+use twilight_model::user::User;
+
+/// The channel moderation actions are logged to. This is the modlog channel if it is enabled,
+/// otherwise the fallback, usually the channel the command was used in.
+pub fn modlog_channel(guild_data: &Guild, fallback: ChannelId) -> ChannelId {
+    if guild_data.modlog && guild_data.modlog_channel > 0 {
+        ChannelId(guild_data.modlog_channel as u64)
+    } else {
+        fallback
+    }
+}
+
+/// Posts an embed to the modlog, see `modlog_channel`
+pub async fn send_modlog(ctx: &Context, guild_data: &Guild, fallback: ChannelId, embed: Embed) -> Result<Message, Box<dyn Error + Send + Sync>> {
+    let message = ctx.http.create_message(modlog_channel(guild_data, fallback))
+        .embed(embed)?
+        .await?;
+
+    Ok(message)
+}
+
+/// Posts a case's modlog entry and records where it was posted, so the entry can be edited later
+pub async fn log_case(ctx: &Context, guild_data: &Guild, fallback: ChannelId, case: Case<Utc>, embed: Embed) -> Result<Case<Utc>, Box<dyn Error + Send + Sync>> {
+    let message = send_modlog(ctx, guild_data, fallback, embed).await?;
+    let case = ctx.db.set_case_modlog(case.guild_id, case.case_number, message.channel_id.0 as i64, message.id.0 as i64)?;
+
+    Ok(case)
+}
+
+/// Replaces the reason line of a modlog entry, adding one if it has none
+pub fn replace_reason(mut embed: Embed, reason: &str) -> Embed {
+    let description = embed.description.unwrap_or_default();
+    let mut lines = description.lines()
+        .filter(|line| !line.starts_with("**Reason:**"))
+        .map(|line| line.to_string())
+        .collect::<Vec<String>>();
+    lines.push(format!("**Reason:** {}", reason));
+    embed.description = Some(lines.join("\n"));
+    embed
+}
+
+/// Builds the modlog entry for a moderation action
+pub fn action_embed(title: &str, color: u32, user: &User, moderator: &User, case: Option<&Case<Utc>>, duration: Option<i64>, reason: &Option<String>) -> Result<Embed, Box<dyn Error + Send + Sync>> {
+    let mut description = format!("**User:** {}#{} ({})\n**Moderator:** {}#{} ({})",
+        user.name,
+        user.discriminator,
+        user.id.0,
+        moderator.name,
+        moderator.discriminator,
+        moderator.id.0);
+    if let Some(duration) = duration {
+        description.push_str(format!("\n**Duration:** {}", seconds_to_hrtime(duration as usize)).as_str());
+    }
+    if let Some(reason) = reason {
+        description.push_str(format!("\n**Reason:** {}", reason).as_str());
+    }
+
+    let mut embed = EmbedBuilder::new()
+        .title(title)
+        .color(color)
+        .thumbnail(ImageSource::url(user_avatar_url(user))?)
+        .description(description)
+        .timestamp(Utc::now().to_rfc3339());
+    if let Some(case) = case {
+        embed = embed.footer(EmbedFooterBuilder::new(format!("Case {}", case.case_number)));
+    }
+
+    Ok(embed.build()?)
+}
+
+/// The actions a warning escalation rule can take
+pub const ESCALATION_ACTIONS: [&str; 3] = ["mute", "kick", "ban"];
+
+/// The bot's own user, used as the moderator for automatic actions
+pub async fn bot_user(ctx: &Context) -> Result<User, Box<dyn Error + Send + Sync>> {
+    if let Some(user) = ctx.cache.user(ctx.user.id) {
+        return Ok((*user).clone());
+    }
+    match ctx.http.user(ctx.user.id).await? {
+        Some(user) => Ok(user),
+        None => Err("Unable to fetch the current user".into()),
+    }
+}
+
+/// Finds the role named `Muted` in the cache
+pub fn find_mute_role(guild_id: GuildId, ctx: &Context) -> Option<Arc<Role>> {
+    ctx.cache.guild_roles(guild_id)?
+        .iter()
+        .filter_map(|role_id| ctx.cache.role(*role_id))
+        .find(|role| role.name.to_lowercase() == "muted")
+}
+
+/// The position of the highest role a member has. The guild owner always ranks highest.
+pub fn highest_role_position(guild_id: GuildId, user_id: UserId, ctx: &Context) -> i64 {
+    if let Some(guild) = ctx.cache.guild(guild_id) {
+        if guild.owner_id == user_id { return i64::MAX; }
+    }
+    match ctx.cache.member(guild_id, user_id) {
+        Some(member) => member.roles.iter()
+            .filter_map(|role_id| ctx.cache.role(*role_id))
+            .map(|role| role.position)
+            .max()
+            .unwrap_or(-1),
+        None => -1,
+    }
+}
+
+/// Whether `moderator` ranks above `target` in the role hierarchy
+pub fn can_moderate(guild_id: GuildId, moderator: UserId, target: UserId, ctx: &Context) -> bool {
+    moderator != target && highest_role_position(guild_id, moderator, ctx) > highest_role_position(guild_id, target, ctx)
+}
+
+/// Mutes a member by giving them the mute role, then records a case and posts it to the modlog.
+/// If a duration is given, an unmute timer is scheduled.
+pub async fn mute(ctx: &Context, guild_data: &Guild, guild_id: GuildId, mute_role: &Role, user: &User, moderator: &User, duration: Option<i64>, reason: Option<String>, fallback: ChannelId) -> Result<Case<Utc>, Box<dyn Error + Send + Sync>> {
+    let request = ctx.http.add_guild_member_role(guild_id, user.id, mute_role.id);
+    match &reason {
+        Some(r) => { request.reason(r.clone())?.await?; },
+        None => { request.await?; },
+    }
+
+    let case = ctx.db.new_case(user.id.0 as i64, guild_id.0 as i64, "Mute".to_string(), reason.clone(), moderator.id.0 as i64)?;
+    if let Some(duration) = duration {
+        let payload = TimerPayload::Unmute {
+            user_id: user.id,
+            guild_id,
+            role_id: mute_role.id,
+            channel_id: modlog_channel(guild_data, fallback),
+            duration,
+        };
+        let start_time = Utc::now().timestamp();
+        ctx.tc.schedule(payload, start_time, start_time + duration)?;
+    }
+
+    let embed = action_embed("Member Muted", colors::RED, user, moderator, Some(&case), duration, &reason)?;
+    let case = log_case(ctx, guild_data, fallback, case, embed).await?;
+
+    Ok(case)
+}
+
+/// Removes the mute role from a member, cancels any pending unmute timers and posts to the modlog
+pub async fn unmute(ctx: &Context, guild_data: &Guild, guild_id: GuildId, mute_role: &Role, user: &User, moderator: &User, reason: Option<String>, fallback: ChannelId) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let request = ctx.http.remove_guild_member_role(guild_id, user.id, mute_role.id);
+    match &reason {
+        Some(r) => { request.reason(r.clone())?.await?; },
+        None => { request.await?; },
+    }
+
+    for timer in ctx.db.get_user_timers(user.id.0 as i64)? {
+        if let Ok(TimerPayload::Unmute { guild_id: g, .. }) = TimerPayload::from_timer(&timer) {
+            if g == guild_id { ctx.tc.cancel(timer.id)?; }
+        }
+    }
+
+    let embed = action_embed("Member Unmuted", colors::GREEN, user, moderator, None, None, &reason)?;
+    send_modlog(ctx, guild_data, fallback, embed).await?;
+
+    Ok(())
+}
+
+/// Kicks a member, then records a case and posts it to the modlog
+pub async fn kick(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, moderator: &User, reason: Option<String>, fallback: ChannelId) -> Result<Case<Utc>, Box<dyn Error + Send + Sync>> {
+    let request = ctx.http.remove_guild_member(guild_id, user.id);
+    match &reason {
+        Some(r) => { request.reason(r.clone())?.await?; },
+        None => { request.await?; },
+    }
+
+    let case = ctx.db.new_case(user.id.0 as i64, guild_id.0 as i64, "Kick".to_string(), reason.clone(), moderator.id.0 as i64)?;
+    let embed = action_embed("Member Kicked", colors::RED, user, moderator, Some(&case), None, &reason)?;
+    let case = log_case(ctx, guild_data, fallback, case, embed).await?;
+
+    Ok(case)
+}
+
+/// The kind of ban to apply, see `ban`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BanKind {
+    /// A permanent ban
+    Ban,
+    /// A ban which is lifted immediately, used to clear a user's messages
+    Softban,
+    /// A ban which is lifted by a timer after the given number of seconds
+    Tempban(i64),
+}
+
+/// Bans a user, deleting up to `delete_days` days of their messages, then records a case and posts it to the modlog.
+/// Softbans unban the user right away, tempbans schedule an unban timer.
+pub async fn ban(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, moderator: &User, kind: BanKind, delete_days: u64, reason: Option<String>, fallback: ChannelId) -> Result<Case<Utc>, Box<dyn Error + Send + Sync>> {
+    let request = ctx.http.create_ban(guild_id, user.id)
+        .delete_message_days(u64::min(delete_days, 7))?;
+    match &reason {
+        Some(r) => { request.reason(r.clone())?.await?; },
+        None => { request.await?; },
+    }
+
+    let (casetype, title, duration) = match kind {
+        BanKind::Ban => ("Ban", "Member Banned", None),
+        BanKind::Softban => {
+            ctx.http.delete_ban(guild_id, user.id).await?;
+            ("Softban", "Member Softbanned", None)
+        },
+        BanKind::Tempban(duration) => {
+            let payload = TimerPayload::Unban {
+                user_id: user.id,
+                guild_id,
+                channel_id: modlog_channel(guild_data, fallback),
+                duration,
+            };
+            let start_time = Utc::now().timestamp();
+            ctx.tc.schedule(payload, start_time, start_time + duration)?;
+            ("Tempban", "Member Temporarily Banned", Some(duration))
+        },
+    };
+
+    let case = ctx.db.new_case(user.id.0 as i64, guild_id.0 as i64, casetype.to_string(), reason.clone(), moderator.id.0 as i64)?;
+    let embed = action_embed(title, colors::RED, user, moderator, Some(&case), duration, &reason)?;
+    let case = log_case(ctx, guild_data, fallback, case, embed).await?;
+
+    Ok(case)
+}
+
+/// Locks channels, scheduling them to be unlocked if a duration is given, and posts to the modlog.
+/// Returns the channels which were locked; channels already locked or which couldn't be locked are skipped.
+pub async fn lock(ctx: &Context, guild_data: &Guild, guild_id: GuildId, channels: &[ChannelId], moderator: &User, duration: Option<i64>, reason: Option<String>, fallback: ChannelId) -> Result<Vec<ChannelId>, Box<dyn Error + Send + Sync>> {
+    let mut locked = Vec::new();
+    for channel_id in channels {
+        match lockdown::lock_channel(ctx, guild_id, *channel_id, false).await {
+            Ok(true) => { locked.push(*channel_id); },
+            Ok(false) => {},
+            Err(why) => { event!(Level::DEBUG, "Unable to lock {} in {}: {}", channel_id, guild_id, why); },
+        }
+    }
+    if locked.is_empty() { return Ok(locked); }
+
+    if let Some(duration) = duration {
+        let start_time = Utc::now().timestamp();
+        for channel_id in locked.iter() {
+            let payload = TimerPayload::Unlock {
+                channel_id: *channel_id,
+                guild_id,
+                log_channel: modlog_channel(guild_data, fallback),
+                duration,
+            };
+            ctx.tc.schedule(payload, start_time, start_time + duration)?;
+        }
+    }
+
+    let embed = lock_embed(if locked.len() == 1 { "Channel Locked" } else { "Channels Locked" }, colors::RED, &locked, moderator, duration, &reason)?;
+    send_modlog(ctx, guild_data, fallback, embed).await?;
+
+    Ok(locked)
+}
+
+/// Unlocks channels, cancelling their unlock timers, and posts to the modlog.
+/// Returns the channels which were unlocked; channels which weren't locked are skipped.
+pub async fn unlock(ctx: &Context, guild_data: &Guild, guild_id: GuildId, channels: &[ChannelId], moderator: &User, reason: Option<String>, fallback: ChannelId) -> Result<Vec<ChannelId>, Box<dyn Error + Send + Sync>> {
+    let mut unlocked = Vec::new();
+    for channel_id in channels {
+        match lockdown::unlock_channel(ctx, guild_id, *channel_id).await {
+            Ok(true) => { unlocked.push(*channel_id); },
+            Ok(false) => {},
+            Err(why) => { event!(Level::DEBUG, "Unable to unlock {} in {}: {}", channel_id, guild_id, why); },
+        }
+    }
+    if unlocked.is_empty() { return Ok(unlocked); }
+
+    let embed = lock_embed(if unlocked.len() == 1 { "Channel Unlocked" } else { "Channels Unlocked" }, colors::GREEN, &unlocked, moderator, None, &reason)?;
+    send_modlog(ctx, guild_data, fallback, embed).await?;
+
+    Ok(unlocked)
+}
+
+/// Builds the modlog entry for locking or unlocking channels
+fn lock_embed(title: &str, color: u32, channels: &[ChannelId], moderator: &User, duration: Option<i64>, reason: &Option<String>) -> Result<Embed, Box<dyn Error + Send + Sync>> {
+    let mut description = format!("**Channels:** {}\n**Moderator:** {}#{} ({})",
+        channels.iter().map(|c| c.mention().to_string()).collect::<Vec<String>>().join(", "),
+        moderator.name,
+        moderator.discriminator,
+        moderator.id.0);
+    if let Some(duration) = duration {
+        description.push_str(format!("\n**Duration:** {}", seconds_to_hrtime(duration as usize)).as_str());
+    }
+    if let Some(reason) = reason {
+        description.push_str(format!("\n**Reason:** {}", reason).as_str());
+    }
+
+    let embed = EmbedBuilder::new()
+        .title(title)
+        .color(color)
+        .description(description)
+        .timestamp(Utc::now().to_rfc3339())
+        .build()?;
+
+    Ok(embed)
+}
+
+/// Records a warning as a case and posts it to the modlog, then applies the escalation rule it triggers, if any
+pub async fn warn(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, moderator: &User, reason: Option<String>, fallback: ChannelId) -> Result<Case<Utc>, Box<dyn Error + Send + Sync>> {
+    let case = ctx.db.new_case(user.id.0 as i64, guild_id.0 as i64, "Warn".to_string(), reason.clone(), moderator.id.0 as i64)?;
+    let embed = action_embed("Member Warned", colors::YELLOW, user, moderator, Some(&case), None, &reason)?;
+    let case = log_case(ctx, guild_data, fallback, case, embed).await?;
+
+    if let Some((rule, count)) = triggered_rule(ctx, guild_id, user.id)? {
+        if let Err(why) = escalate(ctx, guild_data, guild_id, user, &rule, count, fallback).await {
+            event!(Level::WARN, "Unable to apply warn rule {} to {} in {}: {}", rule.id, user.id, guild_id, why);
+        }
+    }
+
+    Ok(case)
+}
+
+/// Finds the rule a user's active warnings have just reached. Rules fire once, when the count within their
+/// period equals their threshold. If several fire at once the one with the highest threshold wins.
+fn triggered_rule(ctx: &Context, guild_id: GuildId, user_id: UserId) -> Result<Option<(WarnRule, i64)>, Box<dyn Error + Send + Sync>> {
+    let mut triggered = None;
+    for rule in ctx.db.get_warn_rules(guild_id.0 as i64)? {
+        let since = if rule.period > 0 { Some(Utc::now() - Duration::seconds(rule.period)) } else { None };
+        let count = ctx.db.count_warns(user_id.0 as i64, guild_id.0 as i64, since)?;
+        if count == rule.warns as i64 {
+            triggered = Some((rule, count));
+        }
+    }
+
+    Ok(triggered)
+}
+
+/// Applies a warning escalation rule through the usual mute, kick and ban paths, with the bot as moderator
+async fn escalate(ctx: &Context, guild_data: &Guild, guild_id: GuildId, user: &User, rule: &WarnRule, count: i64, fallback: ChannelId) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let moderator = bot_user(ctx).await?;
+    let reason = Some(format!("Reached {} warnings (rule {})", count, rule.id));
+    match rule.action.as_str() {
+        "mute" => {
+            let mute_role = match find_mute_role(guild_id, ctx) {
+                Some(role) => role,
+                None => return Err("No mute role".into()),
+            };
+            if let Some(member) = ctx.cache.member(guild_id, user.id) {
+                if member.roles.contains(&mute_role.id) { return Ok(()); }
+            }
+            mute(ctx, guild_data, guild_id, &mute_role, user, &moderator, rule.duration, reason, fallback).await?;
+        },
+        "kick" => {
+            kick(ctx, guild_data, guild_id, user, &moderator, reason, fallback).await?;
+        },
+        "ban" => {
+            let kind = match rule.duration {
+                Some(duration) => BanKind::Tempban(duration),
+                None => BanKind::Ban,
+            };
+            ban(ctx, guild_data, guild_id, user, &moderator, kind, 0, reason, fallback).await?;
+        },
+        action => return Err(format!("Unknown escalation action {}", action).into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use crate::core::consts::*;
+use crate::core::utils::{hrtime_to_seconds, parse_timezone};
+use chrono::{Datelike, TimeZone};
+use chrono::offset::LocalResult;
+use chrono_tz::Tz;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// How often a recurring timer is re-armed after it fires.
+/// Stored as text in `timers.recurrence`, using the `Display` output of this type.
+#[derive(Clone, Debug)]
+pub enum Recurrence {
+    /// A fixed interval in seconds
+    Interval(i64),
+    /// A cron-like schedule, evaluated in its own timezone
+    Cron(Cron),
+}
+
+impl Recurrence {
+    /// Parses either a five field cron expression (`minute hour day month weekday`),
+    /// one of `hourly`, `daily` or `weekly`, or a time_resolvable interval of at least a minute.
+    /// A cron expression may be followed by a timezone name, otherwise it is evaluated in UTC.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim();
+        match input.to_lowercase().as_str() {
+            "hourly" => { return Some(Recurrence::Interval(HOUR as i64)) },
+            "daily" => { return Some(Recurrence::Interval(DAY as i64)) },
+            "weekly" => { return Some(Recurrence::Interval(WEEK as i64)) },
+            _ => {},
+        }
+        if matches!(input.split_whitespace().count(), 5 | 6) {
+            if let Some(cron) = Cron::parse(input) {
+                return Some(Recurrence::Cron(cron));
+            }
+        }
+        match hrtime_to_seconds(input.to_string()) {
+            secs if secs >= MIN as i64 => Some(Recurrence::Interval(secs)),
+            _ => None,
+        }
+    }
+
+    /// Evaluates a cron schedule in the given timezone, unless it named its own. Intervals are unaffected.
+    pub fn in_timezone(self, tz: Tz) -> Self {
+        match self {
+            Recurrence::Cron(cron) => Recurrence::Cron(Cron { tz: cron.tz.or(Some(tz)), ..cron }),
+            interval => interval,
+        }
+    }
+
+    /// The first occurrence strictly after the given timestamp
+    pub fn next_after(&self, after: i64) -> Option<i64> {
+        match self {
+            Recurrence::Interval(secs) => Some(after + secs),
+            Recurrence::Cron(cron) => cron.next_after(after),
+        }
+    }
+
+    /// The time between the first two occurrences after the given timestamp
+    pub fn period(&self, after: i64) -> Option<i64> {
+        let first = self.next_after(after)?;
+        Some(self.next_after(first)? - first)
+    }
+
+    /// A human readable description of the schedule
+    pub fn describe(&self) -> String {
+        match self {
+            Recurrence::Interval(secs) => format!("every {}", crate::core::utils::seconds_to_hrtime(*secs as usize)),
+            Recurrence::Cron(cron) => format!("`{}` ({})", cron.expression, cron.tz.unwrap_or(Tz::UTC).name()),
+        }
+    }
+}
+
+impl Display for Recurrence {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Recurrence::Interval(secs) => write!(f, "{}s", secs),
+            Recurrence::Cron(Cron { expression, tz: Some(tz), .. }) => write!(f, "{} {}", expression, tz.name()),
+            Recurrence::Cron(cron) => write!(f, "{}", cron.expression),
+        }
+    }
+}
+
+/// A parsed cron expression. Each field is stored as a bitmask of the values it allows.
+/// Supports `*`, single values, ranges (`1-5`), steps (`*/15`, `0-30/10`) and comma separated lists.
+#[derive(Clone, Debug)]
+pub struct Cron {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+    /// The timezone the expression is evaluated in, UTC if unset
+    tz: Option<Tz>,
+}
+
+impl Cron {
+    pub fn parse(input: &str) -> Option<Self> {
+        let mut fields = input.split_whitespace().collect::<Vec<&str>>();
+        let tz = match fields.len() {
+            5 => None,
+            6 => Some(parse_timezone(fields.pop()?)?),
+            _ => { return None; },
+        };
+        let mut weekdays = parse_cron_field(fields[4], 0, 7)?;
+        // Both 0 and 7 are Sunday
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Some(Self {
+            expression: fields.join(" "),
+            minutes: parse_cron_field(fields[0], 0, 59)?,
+            hours: parse_cron_field(fields[1], 0, 23)?,
+            days: parse_cron_field(fields[2], 1, 31)?,
+            months: parse_cron_field(fields[3], 1, 12)?,
+            weekdays,
+            any_day: fields[2].starts_with('*'),
+            any_weekday: fields[4].starts_with('*'),
+            tz,
+        })
+    }
+
+    /// Like standard cron, a day matches either field when both day of month and weekday are restricted
+    fn matches_day(&self, month: u32, day: u32, weekday: u32) -> bool {
+        if self.months & (1 << month) == 0 { return false; }
+        let day_match = self.days & (1 << day) != 0;
+        let weekday_match = self.weekdays & (1 << weekday) != 0;
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_match,
+            (false, true) => day_match,
+            (false, false) => day_match || weekday_match,
+        }
+    }
+
+    pub fn next_after(&self, after: i64) -> Option<i64> {
+        // Start at the next whole minute
+        let start = (after / MIN as i64 + 1) * MIN as i64;
+        let tz = self.tz.unwrap_or(Tz::UTC);
+        let mut date = tz.timestamp(start, 0).naive_local().date();
+        // Give up after five years, which covers every valid expression including leap days
+        for _ in 0..(366 * 5) {
+            if self.matches_day(date.month(), date.day(), date.weekday().num_days_from_sunday()) {
+                for hour in 0..24 {
+                    if self.hours & (1 << hour) == 0 { continue; }
+                    for minute in 0..60 {
+                        if self.minutes & (1 << minute) == 0 { continue; }
+                        // Times skipped by a daylight saving change never occur, repeated ones fire once
+                        let candidate = match tz.from_local_datetime(&date.and_hms(hour, minute, 0)) {
+                            LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => dt.timestamp(),
+                            LocalResult::None => { continue; },
+                        };
+                        if candidate >= start { return Some(candidate); }
+                    }
+                }
+            }
+            date = date.succ();
+        }
+        None
+    }
+}
+
+fn parse_cron_field(field: &str, min: u32, max: u32) -> Option<u64> {
+    let mut mask = 0u64;
+    for part in field.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(i) => (&part[..i], part[i+1..].parse::<u32>().ok().filter(|s| *s > 0)?),
+            None => (part, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some(i) = range.find('-') {
+            (range[..i].parse::<u32>().ok()?, range[i+1..].parse::<u32>().ok()?)
+        } else {
+            let value = range.parse::<u32>().ok()?;
+            (value, if step > 1 { max } else { value })
+        };
+        if start < min || end > max || start > end { return None; }
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Some(mask)
+}
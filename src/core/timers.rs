@@ -1,27 +1,117 @@
 use crate::core::consts::*;
+use crate::core::lockdown;
+use crate::core::recurrence::Recurrence;
 use crate::core::utils::*;
 use crate::db::DatabaseConnection;
+use crate::db::models::Timer;
 use chrono::Utc;
-use futures::{StreamExt, channel::mpsc::{
-    Sender,
-    Receiver,
-    channel
-}};
-use futures::future;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tracing::{event, Level};
 use twilight_embed_builder::EmbedBuilder;
 use twilight_mention::Mention;
+use std::collections::BTreeMap;
 use std::error::Error;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Notify;
 use twilight_cache_inmemory::InMemoryCache as Cache;
 use twilight_http::Client as HttpClient;
 use twilight_model::id::{ChannelId, GuildId, RoleId, UserId};
 
+/// The action a timer performs when it expires. This is stored as JSON in `timers.payload`,
+/// tagged by the variant name under `type`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum TimerPayload {
+    Reminder {
+        channel_id: ChannelId,
+        #[serde(default)]
+        guild_id: Option<GuildId>,
+        user_id: UserId,
+        duration: i64,
+        message: String,
+        #[serde(default)]
+        dm: bool,
+    },
+    Unmute {
+        user_id: UserId,
+        guild_id: GuildId,
+        role_id: RoleId,
+        channel_id: ChannelId,
+        duration: i64,
+    },
+    Unban {
+        user_id: UserId,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        duration: i64,
+    },
+    Cooldown {
+        user_id: UserId,
+        guild_id: GuildId,
+        member_role: RoleId,
+        cooldown_role: RoleId,
+    },
+    Announcement {
+        channel_id: ChannelId,
+        guild_id: GuildId,
+        content: String,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        embed: bool,
+    },
+    Unlock {
+        channel_id: ChannelId,
+        guild_id: GuildId,
+        log_channel: ChannelId,
+        duration: i64,
+    },
+}
+
+/// How many times a timer is attempted before it is marked as failed
+pub const MAX_ATTEMPTS: i32 = 5;
+/// The delay before the first retry, doubled on every subsequent attempt
+const RETRY_BASE: i64 = 30;
+/// The longest a failed timer will wait before being retried
+const RETRY_MAX: i64 = HOUR as i64;
+
+impl TimerPayload {
+    /// Deserializes the payload of a stored timer
+    pub fn from_timer(timer: &Timer) -> Result<Self, serde_json::Error> {
+        serde_json::from_value(timer.payload.clone())
+    }
+
+    /// The user a timer concerns. This is stored in `timers.owner_id` so timers can be looked up by user.
+    pub fn owner(&self) -> Option<UserId> {
+        match self {
+            TimerPayload::Reminder { user_id, .. } => Some(*user_id),
+            TimerPayload::Unmute { user_id, .. } => Some(*user_id),
+            TimerPayload::Unban { user_id, .. } => Some(*user_id),
+            TimerPayload::Cooldown { user_id, .. } => Some(*user_id),
+            TimerPayload::Announcement { .. } => None,
+            TimerPayload::Unlock { .. } => None,
+        }
+    }
+
+    /// The guild a timer belongs to, if any
+    pub fn guild(&self) -> Option<GuildId> {
+        match self {
+            TimerPayload::Reminder { guild_id, .. } => *guild_id,
+            TimerPayload::Unmute { guild_id, .. } => Some(*guild_id),
+            TimerPayload::Unban { guild_id, .. } => Some(*guild_id),
+            TimerPayload::Cooldown { guild_id, .. } => Some(*guild_id),
+            TimerPayload::Announcement { guild_id, .. } => Some(*guild_id),
+            TimerPayload::Unlock { guild_id, .. } => Some(*guild_id),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct TimerClient {
-    tx: Arc<Mutex<Sender<()>>>,
-    rx: Arc<Mutex<Receiver<()>>>,
+    queue: Arc<Mutex<BTreeMap<(i64, i32), Timer>>>,
+    notify: Arc<Notify>,
     http: HttpClient,
     cache: Cache,
     db: DatabaseConnection,
@@ -29,95 +119,170 @@ pub struct TimerClient {
 
 impl TimerClient {
     pub fn new(http: HttpClient, cache: Cache, db: DatabaseConnection) -> Self {
-        let (tx, rx) = channel(32);
-        
         TimerClient {
-            tx: Arc::new(Mutex::new(tx)),
-            rx: Arc::new(Mutex::new(rx)),
+            queue: Arc::new(Mutex::new(BTreeMap::new())),
+            notify: Arc::new(Notify::new()),
             http,
             cache,
             db
         }
     }
 
+    /// Loads every pending timer from the database and runs them as they expire.
+    /// Timers which expired while the bot was offline are fired immediately.
     pub async fn start(&self) {
+        match self.db.get_pending_timers() {
+            Ok(timers) => {
+                event!(Level::DEBUG, "Loaded {} pending timers", timers.len());
+                let mut queue = self.queue.lock();
+                for timer in timers {
+                    queue.insert((timer.endtime, timer.id), timer);
+                }
+            },
+            Err(why) => { event!(Level::ERROR, "Failed to load timers: {}", why); },
+        }
+
         loop {
-            let rx_lock = self.rx.clone();
-            let mut rx = rx_lock.lock();
-            let f1 = Box::pin(self.run_timer());
-            let f2 = Box::pin(rx.next());
+            let next = self.queue.lock().keys().next().cloned();
+            match next {
+                Some((endtime, _)) => {
+                    let dur = endtime - Utc::now().timestamp();
+                    if dur <= 0 {
+                        self.fire_due();
+                        continue;
+                    }
+                    tokio::select! {
+                        _ = tokio::time::sleep(Duration::from_secs(dur as u64)) => { self.fire_due(); },
+                        _ = self.notify.notified() => {},
+                    }
+                },
+                None => { self.notify.notified().await; },
+            }
+        }
+    }
 
-            future::select(f1, f2).await;
+    /// Removes every expired timer from the queue and runs each of them in its own task
+    fn fire_due(&self) {
+        let now = Utc::now().timestamp();
+        let due = {
+            let mut queue = self.queue.lock();
+            let later = queue.split_off(&(now + 1, i32::MIN));
+            std::mem::replace(&mut *queue, later)
+        };
+
+        for (_, timer) in due {
+            let tc = self.clone();
+            tokio::spawn(async move {
+                tc.fire(timer).await;
+            });
         }
     }
 
-    async fn run_timer(&self) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let db = self.db.clone();
-        let timer = match db.get_earliest_timer() {
-            Ok(timer) => timer,
-            _ => future::pending().await,
+    /// Runs a single timer. On success the timer is removed, or re-armed if it recurs. On failure the
+    /// error is recorded and the timer is either requeued with an exponential backoff or marked as failed.
+    async fn fire(&self, mut timer: Timer) {
+        let result = match TimerPayload::from_timer(&timer) {
+            Ok(payload) => self.run_timer(payload).await,
+            Err(why) => {
+                // An unreadable payload will never succeed, so don't bother retrying it
+                timer.attempts = MAX_ATTEMPTS - 1;
+                Err(Box::new(why) as Box<dyn Error + Send + Sync>)
+            },
         };
 
-        let dur = u64::checked_sub(timer.endtime as u64, Utc::now().timestamp() as u64).unwrap_or(0);
-
-        tokio::time::sleep(Duration::from_secs(dur)).await;
-            
-        let parts = timer.data.split("||").map(|s| s.to_string()).collect::<Vec<String>>();
-        match parts[0].as_str() {
-            "REMINDER" => {
-                // type, channel_id, user_id, dur, reminder
-                let cid = parts[1].parse::<u64>().ok().map(ChannelId);
-                let uid = parts[2].parse::<u64>().ok().map(UserId);
-                let dur = seconds_to_hrtime(parts[3].parse::<usize>().unwrap_or(0));
-                let rem = &parts[4];
-                match (cid, uid) {
-                    (Some(cid), Some(uid)) => { self.reminder(cid, uid, dur, rem).await?; },
-                    _ => (),
+        match result {
+            Ok(_) => {
+                let next = timer.recurrence.as_ref()
+                    .and_then(|r| Recurrence::parse(r))
+                    .and_then(|r| r.next_after(i64::max(timer.endtime, Utc::now().timestamp())));
+                match next {
+                    Some(endtime) => {
+                        timer.endtime = endtime;
+                        timer.attempts = 0;
+                        let t_id = timer.id;
+                        match self.db.update_timer(t_id, timer).and_then(|_| self.db.clear_timer_error(t_id)) {
+                            Ok(timer) => { self.insert(timer); },
+                            Err(why) => { event!(Level::ERROR, "Failed to re-arm recurring timer: {}", why); },
+                        }
+                    },
+                    None => {
+                        if let Err(why) = self.db.del_timer(timer.id) {
+                            event!(Level::ERROR, "Failed to delete timer {}: {}", timer.id, why);
+                        }
+                    },
                 }
             },
-            "UNMUTE" => {
-                // type, user_id, guild_id, mute_role, channel_id, dur
-                let uid = parts[1].parse::<u64>().ok().map(UserId);
-                let gid = parts[2].parse::<u64>().ok().map(GuildId);
-                let rid = parts[3].parse::<u64>().ok().map(RoleId);
-                let cid = parts[4].parse::<u64>().ok().map(ChannelId);
-                match (uid, gid, cid, rid) {
-                    (Some(u), Some(g), Some(c), Some(r)) => { self.unmute(u,g,c,r).await?; },
-                    _ => (),
+            Err(why) => {
+                timer.attempts += 1;
+                timer.last_error = Some(why.to_string());
+                if timer.attempts >= MAX_ATTEMPTS {
+                    timer.failed = true;
+                    event!(Level::ERROR, "Timer {} failed after {} attempts: {}", timer.id, timer.attempts, why);
+                } else {
+                    let delay = i64::min(RETRY_BASE << (timer.attempts - 1), RETRY_MAX);
+                    timer.endtime = Utc::now().timestamp() + delay;
+                    event!(Level::WARN, "Timer {} failed, retrying in {}s: {}", timer.id, delay, why);
                 }
-            },
-            "COOLDOWN" => {
-                // type, user_id, guild_id, member_role_id, cooldown_role_id
-                let uid = parts[1].parse::<u64>().ok().map(UserId);
-                let gid = parts[2].parse::<u64>().ok().map(GuildId);
-                let mrid = parts[3].parse::<u64>().ok().map(RoleId);
-                let crid = parts[4].parse::<u64>().ok().map(RoleId);
-                match (uid, gid, mrid, crid) {
-                    (Some(u), Some(g), Some(m), Some(c)) => { self.cooldown(u,g,m,c).await?; },
-                    _ => (),
+                match self.db.update_timer(timer.id, timer) {
+                    Ok(timer) => {
+                        if !timer.failed { self.insert(timer); }
+                    },
+                    Err(why) => { event!(Level::ERROR, "Failed to record timer failure: {}", why); },
                 }
             },
-            _ => {},
         }
-        db.del_timer(timer.id)?;
-
-        Ok(())
     }
 
-    async fn reminder(&self, channel_id: ChannelId, user_id: UserId, dur: String, reminder: &String) -> Result<(), Box<dyn Error + Send + Sync>> {
-        let content = match self.cache.guild_channel(channel_id) {
-            Some(_) => user_id.mention().to_string(),
-            None => String::new(),
-        };
+    async fn run_timer(&self, payload: TimerPayload) -> Result<(), Box<dyn Error + Send + Sync>> {
+        match payload {
+            TimerPayload::Reminder { channel_id, user_id, duration, message, dm, .. } => {
+                let dur = seconds_to_hrtime(duration as usize);
+                self.reminder(channel_id, user_id, dur, &message, dm).await
+            },
+            TimerPayload::Unmute { user_id, guild_id, role_id, channel_id, .. } => {
+                self.unmute(user_id, guild_id, channel_id, role_id).await
+            },
+            TimerPayload::Unban { user_id, guild_id, channel_id, .. } => {
+                self.unban(user_id, guild_id, channel_id).await
+            },
+            TimerPayload::Cooldown { user_id, guild_id, member_role, cooldown_role } => {
+                self.cooldown(user_id, guild_id, member_role, cooldown_role).await
+            },
+            TimerPayload::Announcement { channel_id, content, title, embed, .. } => {
+                self.announcement(channel_id, &content, title, embed).await
+            },
+            TimerPayload::Unlock { channel_id, guild_id, log_channel, .. } => {
+                self.unlock(guild_id, channel_id, log_channel).await
+            },
+        }
+    }
 
+    /// Sends a reminder to the channel it was created in, or to the user's DMs if requested.
+    /// If the channel can't be reached anymore, the reminder is delivered by DM instead.
+    async fn reminder(&self, channel_id: ChannelId, user_id: UserId, dur: String, reminder: &String, dm: bool) -> Result<(), Box<dyn Error + Send + Sync>> {
         let embed = EmbedBuilder::new()
             .title(format!("Reminder from {} ago", dur))
             .color(colors::MAIN)
             .description(reminder)
             .build()?;
 
-        self.http.create_message(channel_id)
-            .content(content)?
+        if !dm {
+            let content = match self.cache.guild_channel(channel_id) {
+                Some(_) => user_id.mention().to_string(),
+                None => String::new(),
+            };
+
+            match self.http.create_message(channel_id)
+                .content(content)?
+                .embed(embed.clone())?
+                .await {
+                Ok(_) => { return Ok(()) },
+                Err(why) => { event!(Level::DEBUG, "Unable to deliver reminder to {}, falling back to DM: {}", channel_id, why); },
+            }
+        }
+
+        let channel = self.http.create_private_channel(user_id).await?;
+        self.http.create_message(channel.id)
             .embed(embed)?
             .await?;
 
@@ -125,26 +290,47 @@ impl TimerClient {
     }
     
     async fn unmute(&self, user_id: UserId, guild_id: GuildId, channel_id: ChannelId, role_id: RoleId) -> Result<(), Box<dyn Error + Send + Sync>> {
-        match self.cache.user(user_id) {
-            Some(user) => {
-                self.http.remove_guild_member_role(guild_id, user_id, role_id).await?;
-
-                let embed = EmbedBuilder::new()
-                    .title("Member unmuted automatically")
-                    .color(colors::GREEN)
-                    .description(format!("**Member:** {} ({})", user_tag(user), user_id.0))
-                    .build()?;
-
-                self.http.create_message(channel_id)
-                    .embed(embed)?
-                    .await?;
-            },
-            _ => {},
-        }
+        self.http.remove_guild_member_role(guild_id, user_id, role_id).await?;
+
+        let user = match self.cache.user(user_id) {
+            Some(user) => format!("{} ({})", user_tag(user), user_id.0),
+            None => user_id.0.to_string(),
+        };
+        let embed = EmbedBuilder::new()
+            .title("Member unmuted automatically")
+            .color(colors::GREEN)
+            .description(format!("**Member:** {}", user))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+
+        self.http.create_message(channel_id)
+            .embed(embed)?
+            .await?;
 
         Ok(())
     }
     
+    async fn unban(&self, user_id: UserId, guild_id: GuildId, channel_id: ChannelId) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.http.delete_ban(guild_id, user_id).await?;
+
+        let user = match self.http.user(user_id).await? {
+            Some(user) => format!("{}#{} ({})", user.name, user.discriminator, user_id.0),
+            None => user_id.0.to_string(),
+        };
+        let embed = EmbedBuilder::new()
+            .title("Member unbanned automatically")
+            .color(colors::GREEN)
+            .description(format!("**Member:** {}", user))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+
+        self.http.create_message(channel_id)
+            .embed(embed)?
+            .await?;
+
+        Ok(())
+    }
+
     async fn cooldown(&self, user_id: UserId, guild_id: GuildId, mrole_id: RoleId, crole_id: RoleId) -> Result<(), Box<dyn Error + Send + Sync>> {
         self.http.add_guild_member_role(guild_id, user_id, mrole_id).await?;
         self.http.remove_guild_member_role(guild_id, user_id, crole_id).await?;
@@ -152,7 +338,99 @@ impl TimerClient {
         Ok(())
     }
 
+    /// Posts a scheduled announcement, either as plain text or as an embed
+    async fn announcement(&self, channel_id: ChannelId, content: &String, title: Option<String>, embed: bool) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if embed {
+            let mut builder = EmbedBuilder::new()
+                .color(colors::MAIN)
+                .description(content);
+            if let Some(title) = title {
+                builder = builder.title(title);
+            }
+
+            self.http.create_message(channel_id)
+                .embed(builder.build()?)?
+                .await?;
+        } else {
+            self.http.create_message(channel_id)
+                .content(content)?
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Unlocks a channel locked with `lock`. Nothing is posted if it was already unlocked.
+    async fn unlock(&self, guild_id: GuildId, channel_id: ChannelId, log_channel: ChannelId) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if !lockdown::restore_channel(&self.http, &self.db, guild_id, channel_id).await? {
+            return Ok(());
+        }
+
+        let embed = EmbedBuilder::new()
+            .title("Channel unlocked automatically")
+            .color(colors::GREEN)
+            .description(format!("**Channel:** {}", channel_id.mention()))
+            .timestamp(Utc::now().to_rfc3339())
+            .build()?;
+
+        self.http.create_message(log_channel)
+            .embed(embed)?
+            .await?;
+
+        Ok(())
+    }
+
+    /// Stores a new timer and wakes the timer loop so it can be picked up
+    pub fn schedule(&self, payload: TimerPayload, start_time: i64, end_time: i64) -> Result<Timer, Box<dyn Error + Send + Sync>> {
+        self.create(payload, start_time, end_time, None)
+    }
+
+    /// Stores a timer which is re-armed according to `recurrence` every time it fires.
+    /// The first run happens at `end_time` if given, otherwise at the first occurrence after `start_time`.
+    pub fn schedule_recurring(&self, payload: TimerPayload, recurrence: &Recurrence, start_time: i64, end_time: Option<i64>) -> Result<Timer, Box<dyn Error + Send + Sync>> {
+        let end_time = match end_time.or_else(|| recurrence.next_after(start_time)) {
+            Some(end_time) => end_time,
+            None => { return Err("That schedule never occurs".into()) },
+        };
+        self.create(payload, start_time, end_time, Some(recurrence.to_string()))
+    }
+
+    fn create(&self, payload: TimerPayload, start_time: i64, end_time: i64, recurrence: Option<String>) -> Result<Timer, Box<dyn Error + Send + Sync>> {
+        let owner_id = payload.owner().map(|u| u.0 as i64);
+        let guild_id = payload.guild().map(|g| g.0 as i64);
+        let timer = self.db.new_timer(start_time, end_time, serde_json::to_value(&payload)?, owner_id, guild_id, recurrence)?;
+        self.insert(timer.clone());
+
+        Ok(timer)
+    }
+
+    /// Moves a pending timer to a new expiration time, replacing its payload
+    pub fn reschedule(&self, mut timer: Timer, payload: TimerPayload, end_time: i64) -> Result<Timer, Box<dyn Error + Send + Sync>> {
+        self.queue.lock().retain(|_, t| t.id != timer.id);
+        timer.endtime = end_time;
+        timer.payload = serde_json::to_value(&payload)?;
+        let timer = self.db.update_timer(timer.id, timer)?;
+        self.insert(timer.clone());
+
+        Ok(timer)
+    }
+
+    /// Removes a timer from the queue and the database without running it
+    pub fn cancel(&self, timer_id: i32) -> Result<(), Box<dyn Error + Send + Sync>> {
+        self.queue.lock().retain(|_, t| t.id != timer_id);
+        self.db.del_timer(timer_id)?;
+        self.request();
+
+        Ok(())
+    }
+
+    fn insert(&self, timer: Timer) {
+        self.queue.lock().insert((timer.endtime, timer.id), timer);
+        self.request();
+    }
+
+    /// Wakes the timer loop so that it reevaluates the next timer to expire
     pub fn request(&self) {
-        let _ = self.tx.lock().try_send(());
+        self.notify.notify_one();
     }
 }
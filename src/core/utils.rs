@@ -1,6 +1,9 @@
 use crate::Context;
-use crate::db::models::Role as SelfRole;
+use crate::db::models::{Guild, Role as SelfRole};
 use super::consts::*;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime, TimeZone, Timelike, Utc, Weekday};
+use chrono::offset::LocalResult;
+use chrono_tz::Tz;
 use regex::Regex;
 use std::collections::HashMap;
 use std::error::Error;
@@ -14,6 +17,10 @@ use twilight_model::user::User;
 use twilight_mention::Mention;
 
 lazy_static::lazy_static! {
+    static ref ABS_CLOCK: Regex     = Regex::new(r"\b(?:(noon|midnight)|(\d{1,2}):(\d{2})(?::\d{2})?\s*(am|pm)?|(\d{1,2})\s*(am|pm))\b").expect("Failed to create Regex");
+    static ref ABS_DATE: Regex      = Regex::new(r"\b(\d{4})-(\d{1,2})-(\d{1,2})(?:t|\b)").expect("Failed to create Regex");
+    static ref ABS_DAY: Regex       = Regex::new(r"\b(today|tomorrow|mon|tue|wed|thu|fri|sat|sun)[a-z]*\b").expect("Failed to create Regex");
+    static ref ABS_FILLER: Regex    = Regex::new(r"\b(?:at|on|next)\b").expect("Failed to create Regex");
     static ref CHANNEL_MATCH: Regex = Regex::new(r"(?:<#)?(\d{17,})>*?").expect("Failed to create Regex");
     static ref EMBED_ITEM: Regex    = Regex::new(r"\$[^\$]*").expect("Failed to create Regex");
     static ref EMBED_PARTS: Regex   = Regex::new(r"\$?(?P<field>\S+):(?P<value>.*)").expect("Failed to create Regex");
@@ -121,6 +128,29 @@ async fn _parse_user(input: String, guild_id: GuildId, ctx: Context) -> Option<(
     }
 }
 
+/// Parses a user ID or mention, without checking that the user exists
+pub fn parse_user_id<T: Into<String>>(input: T) -> Option<UserId> {
+    input.into()
+        .trim_start_matches("<@")
+        .trim_start_matches('!')
+        .trim_end_matches('>')
+        .parse::<u64>()
+        .ok()
+        .filter(|id| *id > 0)
+        .map(UserId)
+}
+
+/// Resolves a member of the guild, falling back to any user by ID so departed and banned users can be found
+/// Returns the user, and the member if they are in the guild
+pub async fn resolve_user<T: Into<String>>(input: T, guild_id: GuildId, ctx: Context) -> Option<(User, Option<Arc<Member>>)> {
+    let input = input.into();
+    if let Some((_, member)) = parse_user(input.clone(), guild_id, ctx.clone()).await {
+        return Some((member.user.clone(), Some(member)));
+    }
+    let user = ctx.http.user(parse_user_id(input)?).await.ok().flatten()?;
+    Some((user, None))
+}
+
 pub fn parse_channel<T: Into<String>>(input: T, guild_id: GuildId, ctx: Context) -> Option<(ChannelId, Arc<GuildChannel>)> {
     _parse_channel(input.into(), guild_id, ctx)
 }
@@ -240,6 +270,144 @@ pub fn hrtime_to_seconds(time: String) -> i64 {
         })
 }
 
+/// Resolves an absolute time in the given timezone
+/// Example inputs
+/// `at 17:30`
+/// `tomorrow 9am`
+/// `friday at noon`
+/// `2021-08-01 14:00` or `2021-08-01T14:00:00+02:00`
+/// A time without a date is the next time the clock shows it. A day without a time keeps the current time,
+/// while an ISO date without a time means midnight. Returns the UTC timestamp, or None if the input isn't an
+/// absolute time.
+pub fn parse_absolute_time(input: &str, tz: Tz, now: DateTime<Utc>) -> Option<i64> {
+    let input = input.trim().to_lowercase();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(input.to_uppercase().as_str()) {
+        return Some(datetime.timestamp());
+    }
+    let local_now = now.with_timezone(&tz);
+    let mut rest = input.clone();
+    let mut date = None;
+    let mut weekday = None;
+    let mut default_time = local_now.time().with_nanosecond(0)?;
+
+    if let Some(c) = ABS_DATE.captures(input.as_str()) {
+        date = Some(NaiveDate::from_ymd_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?)?);
+        default_time = NaiveTime::from_hms(0, 0, 0);
+        rest = rest.replacen(&c[0], " ", 1);
+    } else if let Some(c) = ABS_DAY.captures(input.as_str()) {
+        let today = local_now.date().naive_local();
+        match &c[1] {
+            "today" => { date = Some(today); },
+            "tomorrow" => { date = Some(today + Duration::days(1)); },
+            day => { weekday = day.parse::<Weekday>().ok(); },
+        }
+        rest = rest.replacen(&c[0], " ", 1);
+    }
+
+    let time = match ABS_CLOCK.captures(rest.clone().as_str()) {
+        Some(c) => {
+            rest = rest.replacen(&c[0], " ", 1);
+            Some(match c.get(1).map(|m| m.as_str()) {
+                Some("noon") => NaiveTime::from_hms(12, 0, 0),
+                Some(_) => NaiveTime::from_hms(0, 0, 0),
+                None => {
+                    let hour = c.get(2).or(c.get(5))?.as_str().parse::<u32>().ok()?;
+                    let minute = c.get(3).map_or(Some(0), |m| m.as_str().parse::<u32>().ok())?;
+                    let hour = match c.get(4).or(c.get(6)).map(|m| m.as_str()) {
+                        Some(_) if hour == 0 || hour > 12 => { return None; },
+                        Some("am") => hour % 12,
+                        Some(_) => hour % 12 + 12,
+                        None => hour,
+                    };
+                    NaiveTime::from_hms_opt(hour, minute, 0)?
+                },
+            })
+        },
+        None => None,
+    };
+
+    // Anything left over means this wasn't an absolute time, such as `1 day 2h`
+    if !ABS_FILLER.replace_all(rest.as_str(), "").trim().is_empty()
+        || (date.is_none() && weekday.is_none() && time.is_none()) {
+        return None;
+    }
+
+    let time = time.unwrap_or(default_time);
+    let today = local_now.date().naive_local();
+    let (date, step) = match (date, weekday) {
+        (Some(date), _) => (date, None),
+        (None, Some(weekday)) => {
+            let ahead = (7 + weekday.num_days_from_monday() as i64 - today.weekday().num_days_from_monday() as i64) % 7;
+            (today + Duration::days(ahead), Some(Duration::weeks(1)))
+        },
+        (None, None) => (today, Some(Duration::days(1))),
+    };
+    let resolve = |date: NaiveDate| match tz.from_local_datetime(&date.and_time(time)) {
+        LocalResult::Single(dt) | LocalResult::Ambiguous(dt, _) => Some(dt.timestamp()),
+        LocalResult::None => None,
+    };
+
+    let timestamp = resolve(date)?;
+    match step {
+        Some(step) if timestamp <= now.timestamp() => resolve(date + step),
+        _ => Some(timestamp),
+    }
+}
+
+/// Converts a time_resolvable to seconds from now. Absolute times are resolved in the given timezone,
+/// anything else is treated as a relative time by `hrtime_to_seconds`.
+/// Absolute times in the past resolve to 0.
+pub fn time_to_seconds(time: String, tz: Tz) -> i64 {
+    let now = Utc::now();
+    match parse_absolute_time(time.as_str(), tz, now) {
+        Some(timestamp) => i64::max(timestamp - now.timestamp(), 0),
+        None => hrtime_to_seconds(time),
+    }
+}
+
+/// Parses a timezone name such as `Europe/Berlin`, ignoring case
+pub fn parse_timezone<T: Into<String>>(input: T) -> Option<Tz> {
+    let input = input.into();
+    let input = input.trim();
+    input.parse::<Tz>().ok()
+        .or_else(|| chrono_tz::TZ_VARIANTS.iter()
+            .find(|tz| tz.name().eq_ignore_ascii_case(input))
+            .cloned())
+}
+
+/// Gets the timezone a user has set, defaulting to UTC
+pub fn user_timezone(user_id: UserId, ctx: &Context) -> Tz {
+    ctx.db.get_user_timezone(user_id.0 as i64)
+        .ok()
+        .flatten()
+        .and_then(|tz| parse_timezone(tz))
+        .unwrap_or(Tz::UTC)
+}
+
+/// Splits items into pages of `per_page` items. The requested page is 1-indexed and clamped to the valid range.
+/// Returns the items on that page, the page number and the total number of pages.
+pub fn paginate<T>(items: &[T], page: usize, per_page: usize) -> (&[T], usize, usize) {
+    let pages = usize::max((items.len() + per_page - 1) / per_page, 1);
+    let page = usize::min(usize::max(page, 1), pages);
+    let start = usize::min((page - 1) * per_page, items.len());
+    let end = usize::min(start + per_page, items.len());
+    (&items[start..end], page, pages)
+}
+
+/// The time a Discord snowflake was created at
+pub fn snowflake_time(id: u64) -> DateTime<Utc> {
+    Utc.timestamp_millis(((id >> 22) + 1_420_070_400_000) as i64)
+}
+
+/// The channel a log type is posted to. This is the channel set with `config log route`, otherwise `fallback`,
+/// usually the audit or modlog channel. The channel ID is 0 if neither is set.
+pub fn log_channel(guild_data: &Guild, log_type: &str, fallback: i64) -> ChannelId {
+    let channel_id = guild_data.log_routes.get(log_type)
+        .and_then(|id| id.as_i64())
+        .unwrap_or(fallback);
+    ChannelId(i64::max(channel_id, 0) as u64)
+}
+
 /// Converts a time in seconds to a human readable string
 pub fn seconds_to_hrtime(secs: usize) -> String {
     let word = ["week", "day", "hour", "min", "sec"];
@@ -2,6 +2,7 @@
 pub mod models;
 mod schema;
 
+use chrono::DateTime;
 use chrono::offset::Utc;
 use diesel::pg::PgConnection;
 use diesel::pg::upsert::excluded;
@@ -14,6 +15,7 @@ use diesel::r2d2::{
 use diesel;
 use self::models::*;
 use self::schema::*;
+use serde_json::Value;
 use std::env;
 use std::ops::Deref;
 use std::sync::Arc;
@@ -100,6 +102,15 @@ impl DatabaseConnection {
             .set(&guild)
             .get_result(self.conn().deref())
     }
+    /// Clear the verification level saved when raid protection was triggered.
+    /// This needs its own query since `update_guild` leaves columns set to None untouched.
+    /// Returns the new guild on success
+    pub fn clear_raid_verification_level(&self, g_id: i64) -> QueryResult<Guild> {
+        use crate::db::schema::guilds::columns::raid_verification_level;
+        diesel::update(guilds::table.find(&g_id))
+            .set(raid_verification_level.eq(None::<i16>))
+            .get_result(self.conn().deref())
+    }
     /// Get the count of guilds in the database
     pub fn count_guilds(&self) -> QueryResult<i64> {
         use diesel::dsl::count_star;
@@ -174,6 +185,25 @@ impl DatabaseConnection {
                 roles.eq(excluded(roles))))
             .execute(self.conn().deref())
     }
+    /// Select the timezone a user has set in any guild
+    /// Returns the timezone name, if any, on success
+    pub fn get_user_timezone(&self, u_id: i64) -> QueryResult<Option<String>> {
+        use crate::db::schema::users::columns::{id, timezone};
+        users::table.select(timezone)
+            .filter(id.eq(&u_id))
+            .filter(timezone.is_not_null())
+            .first(self.conn().deref())
+            .optional()
+            .map(Option::flatten)
+    }
+    /// Set the timezone of a user across every guild
+    /// Returns the number of rows updated on success
+    pub fn set_user_timezone(&self, u_id: i64, tz: Option<String>) -> QueryResult<usize> {
+        use crate::db::schema::users::columns::{id, timezone};
+        diesel::update(users::table.filter(id.eq(&u_id)))
+            .set(timezone.eq(tz))
+            .execute(self.conn().deref())
+    }
     /// Get the count of users in the database
     pub fn count_users(&self) -> QueryResult<i64> {
         use diesel::dsl::count_star;
@@ -258,13 +288,20 @@ impl DatabaseConnection {
             .returning(note)
             .get_result(self.conn().deref())
     }
-    /*
     /// Select a note
     /// Returns the note on success
     pub fn get_note(&self, n_id: i32, u_id: i64, g_id: i64) -> QueryResult<Note<Utc>> {
         notes::table.find((n_id, u_id, g_id))
             .first(self.conn().deref())
-    }*/
+    }
+    /// Change the text of a note by index, user ID, and guild ID.
+    /// Returns the new Note on success.
+    pub fn update_note(&self, n_id: i32, u_id: i64, g_id: i64, text: String) -> QueryResult<Note<Utc>> {
+        use crate::db::schema::notes::columns::note;
+        diesel::update(notes::table.find((n_id, u_id, g_id)))
+            .set(note.eq(text))
+            .get_result(self.conn().deref())
+    }
     /// Select all notes for a user
     /// Returns a vec of notes on success
     pub fn get_notes(&self, u_id: i64, g_id: i64) -> QueryResult<Vec<Note<Utc>>> {
@@ -281,94 +318,192 @@ impl DatabaseConnection {
     }
 
     // Timer Tools
-    /// Add a timer
+    /// Add a timer with a serialized payload
     /// Returns the timer on success.
-    pub fn new_timer(&self, starttime: i64, endtime: i64, data: String) -> QueryResult<Timer> {
+    pub fn new_timer(&self, starttime: i64, endtime: i64, payload: Value, owner_id: Option<i64>, guild_id: Option<i64>, recurrence: Option<String>) -> QueryResult<Timer> {
         let timer = NewTimer {
             starttime,
             endtime,
-            data,
+            payload,
+            owner_id,
+            guild_id,
+            recurrence,
         };
         diesel::insert_into(timers::table)
             .values(&timer)
             .get_result(self.conn().deref())
     }
     /// Delete a timer with the given ID.
-    /// Returns the note data on success.
-    pub fn del_timer(&self, t_id: i32) -> QueryResult<String> {
-        use crate::db::schema::timers::columns::{id, data};
+    /// Returns the timer payload on success.
+    pub fn del_timer(&self, t_id: i32) -> QueryResult<Value> {
+        use crate::db::schema::timers::columns::{id, payload};
         diesel::delete(timers::table)
             .filter(id.eq(&t_id))
-            .returning(data)
+            .returning(payload)
             .get_result(self.conn().deref())
     }
-    /*
     /// Select a timer
     /// Returns the timer on success
     pub fn get_timer(&self, t_id: i32) -> QueryResult<Timer> {
         timers::table.find(t_id)
             .first(self.conn().deref())
-    }*/
+    }
+    /// Select all pending timers owned by a user, soonest first
+    /// Returns a vec of timers on success
+    pub fn get_user_timers(&self, u_id: i64) -> QueryResult<Vec<Timer>> {
+        use crate::db::schema::timers::columns::{owner_id, failed, endtime};
+        timers::table.filter(owner_id.eq(&u_id))
+            .filter(failed.eq(false))
+            .order(endtime.asc())
+            .get_results(self.conn().deref())
+    }
+    /// Select all pending timers belonging to a guild, soonest first
+    /// Returns a vec of timers on success
+    pub fn get_guild_timers(&self, g_id: i64) -> QueryResult<Vec<Timer>> {
+        use crate::db::schema::timers::columns::{guild_id, failed, endtime};
+        timers::table.filter(guild_id.eq(&g_id))
+            .filter(failed.eq(false))
+            .order(endtime.asc())
+            .get_results(self.conn().deref())
+    }
     /// Select all timers
     /// Returns a vec of timers on success
     pub fn get_timers(&self) -> QueryResult<Vec<Timer>> {
         timers::table.get_results(self.conn().deref())
     }
+    /// Select all timers which have not been marked as failed
+    /// Returns a vec of timers on success
+    pub fn get_pending_timers(&self) -> QueryResult<Vec<Timer>> {
+        use crate::db::schema::timers::columns::failed;
+        timers::table.filter(failed.eq(false))
+            .get_results(self.conn().deref())
+    }
+    /// Update a timer
+    /// Returns the new timer on success
+    pub fn update_timer(&self, t_id: i32, timer: Timer) -> QueryResult<Timer> {
+        let target = timers::table.find(&t_id);
+        diesel::update(target)
+            .set(&timer)
+            .get_result(self.conn().deref())
+    }
+    /// Clear the last error recorded on a timer
+    /// Returns the new timer on success
+    pub fn clear_timer_error(&self, t_id: i32) -> QueryResult<Timer> {
+        use crate::db::schema::timers::columns::last_error;
+        diesel::update(timers::table.find(&t_id))
+            .set(last_error.eq(None::<String>))
+            .get_result(self.conn().deref())
+    }
     /// Get the count of timers in the database
     pub fn count_timers(&self) -> QueryResult<i64> {
         use diesel::dsl::count_star;
         timers::table.select(count_star())
             .get_result(self.conn().deref())
     }
-    /// Get the timer with the closest expiration time to the present
-    pub fn get_earliest_timer(&self) -> QueryResult<Timer> {
-        use crate::db::schema::timers::{all_columns, columns::endtime};
-        timers::table.select(all_columns)
-            .order(endtime.asc())
-            .first(self.conn().deref())
-    }
 
     // Case Tools
-    /// Add a Case
+    /// Add a Case with the next case number for the guild. Numbers come from a per-guild counter which
+    /// only ever goes up, so concurrent cases never collide and deleted numbers are never reused.
     /// Returns the Case on success
     pub fn new_case(&self, user_id: i64, guild_id: i64, casetype: String, reason: Option<String>, moderator: i64) -> QueryResult<Case<Utc>> {
-        let case = NewCase {
-            user_id,
-            guild_id,
-            casetype,
-            reason,
-            moderator,
-        };
-        diesel::insert_into(cases::table)
-            .values(&case)
-            .get_result(self.conn().deref())
-    }
-    /*
-    /// Delete a case
+        use crate::db::schema::case_counters::columns::{guild_id as counter_guild, last_case};
+        let conn = self.conn();
+        conn.deref().transaction(|| {
+            let number: i32 = diesel::insert_into(case_counters::table)
+                .values((counter_guild.eq(&guild_id), last_case.eq(1)))
+                .on_conflict(counter_guild)
+                .do_update()
+                .set(last_case.eq(last_case + 1))
+                .returning(last_case)
+                .get_result(conn.deref())?;
+            let case = NewCase {
+                user_id,
+                guild_id,
+                casetype,
+                reason,
+                moderator,
+                case_number: number,
+            };
+            diesel::insert_into(cases::table)
+                .values(&case)
+                .get_result(conn.deref())
+        })
+    }
+    /// Delete a case by guild ID and case number
     /// Returns the case on success.
-    pub fn del_case(&self, c_id: i32, u_id: i64, g_id: i64) -> QueryResult<Case<Utc>> {
-        use db::schema::cases::columns::{id, user_id, guild_id};
-        diesel::delete(cases)
-            .filter(id.eq(&c_id))
-            .filter(user_id.eq(&u_id))
+    pub fn del_case(&self, g_id: i64, number: i32) -> QueryResult<Case<Utc>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number};
+        diesel::delete(cases::table)
             .filter(guild_id.eq(&g_id))
+            .filter(case_number.eq(&number))
             .get_result(self.conn().deref())
     }
-    /// Select a case
+    /// Select a case by guild ID and case number
     /// Returns the case on success
-    pub fn get_case(&self, c_id: i32, u_id: i64, g_id: i64) -> QueryResult<Case<Utc>> {
-        cases::table.find((c_id, u_id, g_id))
+    pub fn get_case(&self, g_id: i64, number: i32) -> QueryResult<Case<Utc>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number};
+        cases::table.filter(guild_id.eq(&g_id))
+            .filter(case_number.eq(&number))
             .first(self.conn().deref())
-    }*/
+    }
     /// Select all cases for a user
     /// Returns a vector of cases on success
     pub fn get_cases(&self, u_id: i64, g_id: i64) -> QueryResult<Vec<Case<Utc>>> {
-        use crate::db::schema::cases::columns::{guild_id, user_id};
+        use crate::db::schema::cases::columns::{guild_id, user_id, case_number};
         cases::table.filter(user_id.eq(&u_id))
             .filter(guild_id.eq(&g_id))
+            .order(case_number.desc())
             .get_results(self.conn().deref())
     }
-    /// Get the count of cases in the database
+    /// Select all cases in a guild, newest first
+    /// Returns a vector of cases on success
+    pub fn get_guild_cases(&self, g_id: i64) -> QueryResult<Vec<Case<Utc>>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number};
+        cases::table.filter(guild_id.eq(&g_id))
+            .order(case_number.desc())
+            .get_results(self.conn().deref())
+    }
+    /// Change the reason of a case
+    /// Returns the new case on success
+    pub fn update_case_reason(&self, g_id: i64, number: i32, text: String) -> QueryResult<Case<Utc>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number, reason};
+        diesel::update(cases::table.filter(guild_id.eq(&g_id)).filter(case_number.eq(&number)))
+            .set(reason.eq(text))
+            .get_result(self.conn().deref())
+    }
+    /// Record the modlog message a case was posted as
+    /// Returns the new case on success
+    pub fn set_case_modlog(&self, g_id: i64, number: i32, channel: i64, message: i64) -> QueryResult<Case<Utc>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number, modlog_channel, modlog_message};
+        diesel::update(cases::table.filter(guild_id.eq(&g_id)).filter(case_number.eq(&number)))
+            .set((modlog_channel.eq(Some(channel)), modlog_message.eq(Some(message))))
+            .get_result(self.conn().deref())
+    }
+    /// Mark a case as pardoned so it no longer counts towards warning escalations
+    /// Returns the new case on success
+    pub fn pardon_case(&self, g_id: i64, number: i32) -> QueryResult<Case<Utc>> {
+        use crate::db::schema::cases::columns::{guild_id, case_number, pardoned};
+        diesel::update(cases::table.filter(guild_id.eq(&g_id)).filter(case_number.eq(&number)))
+            .set(pardoned.eq(true))
+            .get_result(self.conn().deref())
+    }
+    /// Count a user's warnings which haven't been pardoned, optionally only those after `since`
+    /// Returns the count on success
+    pub fn count_warns(&self, u_id: i64, g_id: i64, since: Option<DateTime<Utc>>) -> QueryResult<i64> {
+        use crate::db::schema::cases::columns::{guild_id, user_id, casetype, pardoned, timestamp};
+        use diesel::dsl::count_star;
+        let mut query = cases::table.select(count_star())
+            .filter(user_id.eq(&u_id))
+            .filter(guild_id.eq(&g_id))
+            .filter(casetype.eq("Warn"))
+            .filter(pardoned.eq(false))
+            .into_boxed();
+        if let Some(since) = since {
+            query = query.filter(timestamp.ge(since));
+        }
+        query.get_result(self.conn().deref())
+    }
+
     pub fn count_cases(&self) -> QueryResult<i64> {
         use diesel::dsl::count_star;
         cases::table.select(count_star())
@@ -467,4 +602,106 @@ impl DatabaseConnection {
         hackbans::table.select(count_star())
             .get_result(self.conn().deref())
     }
+
+    // Warn Rule Tools
+    /// Add a warning escalation rule
+    /// Returns the WarnRule on success
+    pub fn new_warn_rule(&self, guild_id: i64, warns: i32, period: i64, action: String, duration: Option<i64>) -> QueryResult<WarnRule> {
+        let rule = NewWarnRule {
+            guild_id,
+            warns,
+            period,
+            action,
+            duration,
+        };
+        diesel::insert_into(warn_rules::table)
+            .values(&rule)
+            .get_result(self.conn().deref())
+    }
+    /// Delete a warning escalation rule
+    /// Returns the WarnRule on success
+    pub fn del_warn_rule(&self, r_id: i32, g_id: i64) -> QueryResult<WarnRule> {
+        use crate::db::schema::warn_rules::columns::{id, guild_id};
+        diesel::delete(warn_rules::table)
+            .filter(id.eq(&r_id))
+            .filter(guild_id.eq(&g_id))
+            .get_result(self.conn().deref())
+    }
+    /// Select all warning escalation rules by guild, lowest threshold first
+    /// Returns Vec<WarnRule> on success
+    pub fn get_warn_rules(&self, g_id: i64) -> QueryResult<Vec<WarnRule>> {
+        use crate::db::schema::warn_rules::columns::{guild_id, warns};
+        warn_rules::table.filter(guild_id.eq(&g_id))
+            .order(warns.asc())
+            .get_results(self.conn().deref())
+    }
+
+    // Filter Tools
+    /// Add a word or regex filter
+    /// Returns the Filter on success
+    pub fn new_filter(&self, guild_id: i64, pattern: String, regex: bool) -> QueryResult<Filter> {
+        let filter = NewFilter {
+            guild_id,
+            pattern,
+            regex,
+        };
+        diesel::insert_into(filters::table)
+            .values(&filter)
+            .get_result(self.conn().deref())
+    }
+    /// Delete a filter
+    /// Returns the Filter on success
+    pub fn del_filter(&self, f_id: i32, g_id: i64) -> QueryResult<Filter> {
+        use crate::db::schema::filters::columns::{id, guild_id};
+        diesel::delete(filters::table)
+            .filter(id.eq(&f_id))
+            .filter(guild_id.eq(&g_id))
+            .get_result(self.conn().deref())
+    }
+    /// Select all filters by guild
+    /// Returns Vec<Filter> on success
+    pub fn get_filters(&self, g_id: i64) -> QueryResult<Vec<Filter>> {
+        use crate::db::schema::filters::columns::{guild_id, id};
+        filters::table.filter(guild_id.eq(&g_id))
+            .order(id.asc())
+            .get_results(self.conn().deref())
+    }
+
+    // Channel Lock Tools
+    /// Record a locked channel along with its previous permission overwrites
+    /// Returns Ok(Some(ChannelLock)) on success or Ok(None) if the channel is already locked
+    pub fn new_channel_lock(&self, channel_id: i64, guild_id: i64, overwrites: Value, raid: bool) -> QueryResult<Option<ChannelLock<Utc>>> {
+        let lock = NewChannelLock {
+            channel_id,
+            guild_id,
+            overwrites,
+            raid,
+        };
+        diesel::insert_into(channel_locks::table)
+            .values(&lock)
+            .on_conflict_do_nothing()
+            .get_result(self.conn().deref())
+            .optional()
+    }
+    /// Delete a channel lock
+    /// Returns the ChannelLock on success
+    pub fn del_channel_lock(&self, c_id: i64) -> QueryResult<ChannelLock<Utc>> {
+        use crate::db::schema::channel_locks::columns::channel_id;
+        diesel::delete(channel_locks::table)
+            .filter(channel_id.eq(&c_id))
+            .get_result(self.conn().deref())
+    }
+    /// Select a channel lock
+    /// Returns the ChannelLock on success
+    pub fn get_channel_lock(&self, c_id: i64) -> QueryResult<ChannelLock<Utc>> {
+        channel_locks::table.find(&c_id)
+            .first(self.conn().deref())
+    }
+    /// Select all locked channels by guild
+    /// Returns Vec<ChannelLock> on success
+    pub fn get_channel_locks(&self, g_id: i64) -> QueryResult<Vec<ChannelLock<Utc>>> {
+        use crate::db::schema::channel_locks::columns::guild_id;
+        channel_locks::table.filter(guild_id.eq(&g_id))
+            .get_results(self.conn().deref())
+    }
 }
@@ -1,4 +1,5 @@
 use chrono::{DateTime, TimeZone, Utc};
+use serde_json::Value;
 use twilight_model::id::UserId;
 use std::fmt::{Display, Formatter, Result as FmtResult};
 use super::schema::*;
@@ -36,6 +37,31 @@ pub struct Guild {
     pub register_cooldown_role: Option<i64>,
     pub register_cooldown_duration: Option<i32>,
     pub cooldown_restricted_roles: Vec<i64>,
+    pub watchlist_channel: i64,
+    pub spam_flood_count: i32,
+    pub spam_flood_seconds: i32,
+    pub spam_flood_action: String,
+    pub spam_duplicate_count: i32,
+    pub spam_duplicate_seconds: i32,
+    pub spam_duplicate_action: String,
+    pub spam_mention_count: i32,
+    pub spam_mention_action: String,
+    pub spam_mute_duration: i64,
+    pub filter_warn: bool,
+    pub invite_filter: bool,
+    pub invite_allow_own: bool,
+    pub blocked_domains: Vec<String>,
+    pub allowed_domains: Vec<String>,
+    pub link_filter_action: String,
+    pub raid_joins: i32,
+    pub raid_seconds: i32,
+    pub raid_account_age: i64,
+    pub raid_action: String,
+    pub raid_channels: Vec<i64>,
+    pub raid_punish: String,
+    pub raid_active: bool,
+    pub raid_verification_level: Option<i16>,
+    pub log_routes: Value,
 }
 
 // Deprecated fields: nickname, roles
@@ -51,6 +77,7 @@ pub struct User<Tz: TimeZone> {
     pub xp: i64,
     pub last_message: DateTime<Tz>,
     pub registered: Option<DateTime<Tz>>,
+    pub timezone: Option<String>,
 }
 
 #[derive(Queryable, Identifiable, AsChangeset, Debug)]
@@ -75,13 +102,19 @@ pub struct Role {
     pub forbidden_roles: Vec<i64>,
 }
 
-#[derive(Queryable, Identifiable, AsChangeset, Debug)]
+#[derive(Queryable, Identifiable, AsChangeset, Clone, Debug)]
 #[primary_key(id)]
 pub struct Timer {
     pub id: i32,
     pub starttime: i64,
     pub endtime: i64,
-    pub data: String,
+    pub payload: Value,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub failed: bool,
+    pub owner_id: Option<i64>,
+    pub guild_id: Option<i64>,
+    pub recurrence: Option<String>,
 }
 
 #[derive(Queryable, Identifiable, AsChangeset, Debug)]
@@ -93,7 +126,11 @@ pub struct Case<Tz: TimeZone> {
     pub casetype: String,
     pub reason: String,
     pub moderator: i64,
-    pub timestamp: DateTime<Tz>
+    pub timestamp: DateTime<Tz>,
+    pub case_number: i32,
+    pub modlog_channel: Option<i64>,
+    pub modlog_message: Option<i64>,
+    pub pardoned: bool,
 }
 
 #[derive(Queryable, Identifiable, AsChangeset, Debug)]
@@ -114,6 +151,36 @@ pub struct Hackban {
     pub reason: Option<String>,
 }
 
+#[derive(Queryable, Identifiable, AsChangeset, Clone, Debug)]
+#[primary_key(channel_id)]
+pub struct ChannelLock<Tz: TimeZone> {
+    pub channel_id: i64,
+    pub guild_id: i64,
+    pub overwrites: Value,
+    pub raid: bool,
+    pub timestamp: DateTime<Tz>,
+}
+
+#[derive(Queryable, Identifiable, AsChangeset, Clone, Debug)]
+#[primary_key(id, guild_id)]
+pub struct Filter {
+    pub id: i32,
+    pub guild_id: i64,
+    pub pattern: String,
+    pub regex: bool,
+}
+
+#[derive(Queryable, Identifiable, AsChangeset, Clone, Debug)]
+#[primary_key(id, guild_id)]
+pub struct WarnRule {
+    pub id: i32,
+    pub guild_id: i64,
+    pub warns: i32,
+    pub period: i64,
+    pub action: String,
+    pub duration: Option<i64>,
+}
+
 // END QUERYABLES
 // INSERTABLES
 
@@ -153,7 +220,10 @@ pub struct NewRole {
 pub struct NewTimer {
     pub starttime: i64,
     pub endtime: i64,
-    pub data: String,
+    pub payload: Value,
+    pub owner_id: Option<i64>,
+    pub guild_id: Option<i64>,
+    pub recurrence: Option<String>,
 }
 
 #[derive(Insertable)]
@@ -164,6 +234,7 @@ pub struct NewCase {
     pub casetype: String,
     pub reason: Option<String>,
     pub moderator: i64,
+    pub case_number: i32,
 }
 
 #[derive(Insertable)]
@@ -175,6 +246,33 @@ pub struct NewTag {
     pub data: String,
 }
 
+#[derive(Insertable)]
+#[table_name="channel_locks"]
+pub struct NewChannelLock {
+    pub channel_id: i64,
+    pub guild_id: i64,
+    pub overwrites: Value,
+    pub raid: bool,
+}
+
+#[derive(Insertable)]
+#[table_name="filters"]
+pub struct NewFilter {
+    pub guild_id: i64,
+    pub pattern: String,
+    pub regex: bool,
+}
+
+#[derive(Insertable)]
+#[table_name="warn_rules"]
+pub struct NewWarnRule {
+    pub guild_id: i64,
+    pub warns: i32,
+    pub period: i64,
+    pub action: String,
+    pub duration: Option<i64>,
+}
+
 // END INSERTABLES
 // OTHER STUFF
 
@@ -189,7 +287,7 @@ pub struct UserUpdate {
 
 impl Display for Guild {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        write!(f, "**Admin Roles:** {}\n**Audit:** {}\n**Audit Channel:** {}\n**Audit Threshold:** {}\n**Autorole:** {}\n**Autoroles:** {}\n**Ignored Channels:** {}\n**Ignore Level:** {}\n**Introduction:** {}\n**Introduction Channel:** {}\n**Introduction Type:** {}\n**Introduction Message:** {}\n**Mod Roles: ** {}\n**Modlog:** {}\n**Modlog Channel:** {}\n**Mute Setup:** {}\n**Prefix:** {}\n**Welcome:** {}\n**Welcome Channel:** {}\n**Welcome Type:** {}\n**Welcome Message:** {}\n**Disabled Commands:** {}\n**Disabled Log Types:** {}\n**Register Member Role:** {}\n**Register Cooldown Role:** {}\n**Register Duration:** {}\n**Cooldown Restricted Roles:** {}",
+        write!(f, "**Admin Roles:** {}\n**Audit:** {}\n**Audit Channel:** {}\n**Audit Threshold:** {}\n**Autorole:** {}\n**Autoroles:** {}\n**Ignored Channels:** {}\n**Ignore Level:** {}\n**Introduction:** {}\n**Introduction Channel:** {}\n**Introduction Type:** {}\n**Introduction Message:** {}\n**Mod Roles: ** {}\n**Modlog:** {}\n**Modlog Channel:** {}\n**Mute Setup:** {}\n**Prefix:** {}\n**Welcome:** {}\n**Welcome Channel:** {}\n**Welcome Type:** {}\n**Welcome Message:** {}\n**Disabled Commands:** {}\n**Disabled Log Types:** {}\n**Register Member Role:** {}\n**Register Cooldown Role:** {}\n**Register Duration:** {}\n**Cooldown Restricted Roles:** {}\n**Watchlist Channel:** {}\n**Flood Filter:** {}\n**Duplicate Filter:** {}\n**Mention Filter:** {}\n**Automod Mute Time:** {}\n**Warn On Filter:** {}\n**Invite Filter:** {}\n**Blocked Domains:** {}\n**Allowed Domains:** {}\n**Link Filter Action:** {}\n**Raid Protection:** {}\n**Log Routes:** {}",
             self.admin_roles.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", "),
             self.audit,
             format!("<#{}>", self.audit_channel),
@@ -216,7 +314,22 @@ impl Display for Guild {
             self.register_member_role.map(|e| e.to_string()).unwrap_or("Not set".to_string()),
             self.register_cooldown_role.map(|e| e.to_string()).unwrap_or("Not set".to_string()),
             self.register_cooldown_duration.map(|e| crate::core::utils::seconds_to_hrtime(e as usize).to_string()).unwrap_or("Not set".to_string()),
-            self.cooldown_restricted_roles.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", ")
+            self.cooldown_restricted_roles.iter().map(|e| e.to_string()).collect::<Vec<String>>().join(", "),
+            if self.watchlist_channel > 0 { format!("<#{}>", self.watchlist_channel) } else { "Not set".to_string() },
+            if self.spam_flood_count > 0 { format!("{} messages in {}s, {}", self.spam_flood_count, self.spam_flood_seconds, self.spam_flood_action) } else { "Off".to_string() },
+            if self.spam_duplicate_count > 0 { format!("{} messages in {}s, {}", self.spam_duplicate_count, self.spam_duplicate_seconds, self.spam_duplicate_action) } else { "Off".to_string() },
+            if self.spam_mention_count > 0 { format!("{} mentions, {}", self.spam_mention_count, self.spam_mention_action) } else { "Off".to_string() },
+            crate::core::utils::seconds_to_hrtime(self.spam_mute_duration as usize),
+            self.filter_warn,
+            if self.invite_filter { if self.invite_allow_own { "On, own invites allowed" } else { "On" } } else { "Off" },
+            self.blocked_domains.join(", "),
+            self.allowed_domains.join(", "),
+            self.link_filter_action,
+            if self.raid_joins > 0 { format!("{} joins in {}s, {}, punish: {}{}", self.raid_joins, self.raid_seconds, self.raid_action, self.raid_punish, if self.raid_active { " (active)" } else { "" }) } else { "Off".to_string() },
+            self.log_routes.as_object()
+                .filter(|routes| !routes.is_empty())
+                .map(|routes| routes.iter().map(|(log_type, channel)| format!("{}: <#{}>", log_type, channel)).collect::<Vec<String>>().join(", "))
+                .unwrap_or("None".to_string())
     )}
 }
 
@@ -229,3 +342,23 @@ impl Display for Note<Utc> {
             self.note)
     }
 }
+
+impl Display for WarnRule {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} warns{} → {}{} (ID: {})",
+            self.warns,
+            if self.period > 0 { format!(" in {}", crate::core::utils::seconds_to_hrtime(self.period as usize)) } else { String::new() },
+            self.action,
+            self.duration.map(|d| format!(" for {}", crate::core::utils::seconds_to_hrtime(d as usize))).unwrap_or_default(),
+            self.id)
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "`{}` {} (ID: {})",
+            self.pattern,
+            if self.regex { "regex" } else { "word" },
+            self.id)
+    }
+}
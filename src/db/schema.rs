@@ -7,6 +7,36 @@ table! {
         reason -> Text,
         moderator -> Int8,
         timestamp -> Timestamptz,
+        case_number -> Int4,
+        modlog_channel -> Nullable<Int8>,
+        modlog_message -> Nullable<Int8>,
+        pardoned -> Bool,
+    }
+}
+
+table! {
+    case_counters (guild_id) {
+        guild_id -> Int8,
+        last_case -> Int4,
+    }
+}
+
+table! {
+    channel_locks (channel_id) {
+        channel_id -> Int8,
+        guild_id -> Int8,
+        overwrites -> Jsonb,
+        raid -> Bool,
+        timestamp -> Timestamptz,
+    }
+}
+
+table! {
+    filters (id, guild_id) {
+        id -> Int4,
+        guild_id -> Int8,
+        pattern -> Text,
+        regex -> Bool,
     }
 }
 
@@ -40,6 +70,31 @@ table! {
         register_cooldown_role -> Nullable<Int8>,
         register_cooldown_duration -> Nullable<Int4>,
         cooldown_restricted_roles -> Array<Int8>,
+        watchlist_channel -> Int8,
+        spam_flood_count -> Int4,
+        spam_flood_seconds -> Int4,
+        spam_flood_action -> Text,
+        spam_duplicate_count -> Int4,
+        spam_duplicate_seconds -> Int4,
+        spam_duplicate_action -> Text,
+        spam_mention_count -> Int4,
+        spam_mention_action -> Text,
+        spam_mute_duration -> Int8,
+        filter_warn -> Bool,
+        invite_filter -> Bool,
+        invite_allow_own -> Bool,
+        blocked_domains -> Array<Text>,
+        allowed_domains -> Array<Text>,
+        link_filter_action -> Text,
+        raid_joins -> Int4,
+        raid_seconds -> Int4,
+        raid_account_age -> Int8,
+        raid_action -> Text,
+        raid_channels -> Array<Int8>,
+        raid_punish -> Text,
+        raid_active -> Bool,
+        raid_verification_level -> Nullable<Int2>,
+        log_routes -> Jsonb,
     }
 }
 
@@ -98,7 +153,13 @@ table! {
         id -> Int4,
         starttime -> Int8,
         endtime -> Int8,
-        data -> Text,
+        payload -> Jsonb,
+        attempts -> Int4,
+        last_error -> Nullable<Text>,
+        failed -> Bool,
+        owner_id -> Nullable<Int8>,
+        guild_id -> Nullable<Int8>,
+        recurrence -> Nullable<Text>,
     }
 }
 
@@ -113,11 +174,26 @@ table! {
         xp -> Int8,
         last_message -> Timestamptz,
         registered -> Nullable<Timestamptz>,
+        timezone -> Nullable<Text>,
+    }
+}
+
+table! {
+    warn_rules (id, guild_id) {
+        id -> Int4,
+        guild_id -> Int8,
+        warns -> Int4,
+        period -> Int8,
+        action -> Text,
+        duration -> Nullable<Int8>,
     }
 }
 
 allow_tables_to_appear_in_same_query!(
+    case_counters,
     cases,
+    channel_locks,
+    filters,
     guilds,
     hackbans,
     notes,
@@ -126,4 +202,5 @@ allow_tables_to_appear_in_same_query!(
     tags,
     timers,
     users,
+    warn_rules,
 );
@@ -110,7 +110,7 @@ impl Command for Help {
 
             for (module_name, module) in modules.iter() {
                 if let Some(ref prefix) = module.prefix {
-                    if prefix == &input.to_lowercase() {
+                    if prefix == &input.to_lowercase() || module.prefix_aliases.contains(&input.to_lowercase()) {
                         if let Ok(subcmd) = args.single::<String>() {
                             let name = format!("{} {}", input, &subcmd);
                             if let Some(cmd) = super::command_crawl(subcmd, module) {
@@ -272,6 +272,7 @@ impl Debug for CommandOrAlias {
 #[derive(Debug)]
 pub struct ModuleBuilder {
     pub prefix: Option<String>,
+    pub prefix_aliases: Vec<String>,
     pub commands: HashMap<String, CommandOrAlias>,
     pub required_permissions: Permissions,
     pub help_available: bool,
@@ -286,6 +287,7 @@ impl Default for ModuleBuilder {
     fn default() -> Self {
         Self {
             prefix: None,
+            prefix_aliases: Vec::new(),
             commands: HashMap::new(),
             required_permissions: Permissions::empty(),
             guild_only: false,
@@ -302,6 +304,7 @@ impl ModuleBuilder {
     pub fn build(self) -> Module {
         Module {
             prefix: self.prefix,
+            prefix_aliases: self.prefix_aliases,
             commands: self.commands,
             required_permissions: self.required_permissions,
             guild_only: self.guild_only,
@@ -319,6 +322,15 @@ impl ModuleBuilder {
         self
     }
 
+    /// Sets the prefix to the first item, the rest are accepted as aliases for it
+    pub fn prefixes<S: ToString>(mut self, prefixes: Vec<S>) -> Self {
+        let mut prefixes = prefixes.iter().map(|p| p.to_string());
+        self.prefix = prefixes.next();
+        self.prefix_aliases = prefixes.collect();
+
+        self
+    }
+
     pub fn add_command<S: ToString>(mut self, name: S, command: CommandOrAlias) -> Self {
         self.commands.insert(name.to_string(), command);
 
@@ -366,6 +378,7 @@ impl ModuleBuilder {
 #[non_exhaustive]
 pub struct Module {
     pub prefix: Option<String>,
+    pub prefix_aliases: Vec<String>,
     pub commands: HashMap<String, CommandOrAlias>,
     pub required_permissions: Permissions,
     pub help_available: bool,
@@ -298,7 +298,7 @@ impl Framework {
 pub(crate) fn get_command(modules: &HashMap<String, Arc<Module>>, input: String, mut args: Args) -> Option<(Arc<dyn CommandTrait>, Args)> {
     for module in modules.values() {
         if let Some(module_prefix) = &module.prefix {
-            if module_prefix == &input {
+            if module_prefix == &input || module.prefix_aliases.contains(&input) {
                 match args.single::<String>() {
                     Ok(sub_comm) => {
                         return command_crawl(sub_comm.clone(), module)
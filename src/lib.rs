@@ -5,6 +5,7 @@ pub mod core;
 pub mod db;
 pub mod framework;
 
+use crate::core::automod::AutomodState;
 use crate::core::timers::TimerClient;
 use db::DatabaseConnection;
 use framework::parser::Parser;
@@ -20,6 +21,7 @@ use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct Context {
+    pub automod: AutomodState,
     pub cache: InMemoryCache,
     pub cluster: Cluster,
     pub db: DatabaseConnection,
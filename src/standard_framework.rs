@@ -31,13 +31,26 @@ impl StandardFramework {
             .config(config)
             .add_module("Config", commands::admins::init_config)
             .add_module("Management", commands::admins::init_management)
+            .add_module("Setup", commands::admins::init_setup)
+            .add_module("Scheduled Announcements", commands::admins::init_schedule)
             .add_module("Miscellaneous", commands::general::init_misc)
+            .add_module("Reminders", commands::general::init_reminders)
             .add_module("Self Roles", commands::general::init_roles)
             .add_module("Self Role Management", commands::admins::init_roles)
             .add_module("Tags", commands::general::init_tags)
             .add_module("Database Controls", commands::owner::init_db)
             .add_module("Owner Tools", commands::owner::init)
             .add_module("Mod Role Tools", commands::mods::init_roles)
+            .add_module("Mod Info", commands::mods::init_info)
+            .add_module("Mute", commands::mods::init_mute)
+            .add_module("Kicks and Bans", commands::mods::init_kickbans)
+            .add_module("Hackbans", commands::mods::init_hackbans)
+            .add_module("Cases", commands::mods::init_cases)
+            .add_module("Warnings", commands::mods::init_warnings)
+            .add_module("Notes", commands::mods::init_notes)
+            .add_module("Watchlist", commands::mods::init_watchlist)
+            .add_module("Lockdown", commands::mods::init_lockdown)
+            .add_module("Raid Protection", commands::mods::init_antiraid)
             .build()
     }
 }
\ No newline at end of file